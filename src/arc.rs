@@ -0,0 +1,318 @@
+//! Shared collections that clone in constant time and copy on write.
+
+use crate::{HashableHashMap, HashableHashSet};
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A [`HashableHashSet`] behind an [`Arc`]. Cloning only bumps a reference count, and the first
+/// mutation of a shared set clones its contents (see [`ArcHashableHashSet::make_mut`]).
+///
+/// [`Hash`] and [`Eq`] match those of the underlying set, and equality short-circuits when both
+/// sides share the same allocation. Read access is available through [`Deref`].
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::ArcHashableHashSet;
+///
+/// let a: ArcHashableHashSet<u32> = vec![1, 2].into_iter().collect();
+/// let mut b = a.clone();
+/// assert!(a.ptr_eq(&b));
+/// b.insert(3);
+/// assert!(!a.ptr_eq(&b));
+/// assert_eq!(a.len(), 2);
+/// ```
+pub struct ArcHashableHashSet<V, S = ahash::RandomState>(Arc<HashableHashSet<V, S>>);
+
+impl<V, S> ArcHashableHashSet<V, S> {
+    /// Returns `true` if both sets share the same allocation.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<V: Clone, S: Clone> ArcHashableHashSet<V, S> {
+    /// Returns a mutable reference to the set, first cloning it if it is shared.
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut HashableHashSet<V, S> {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Unwraps the set, cloning it if it is shared.
+    #[inline]
+    pub fn into_inner(self) -> HashableHashSet<V, S> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<V: Clone + Eq + Hash, S: Clone + BuildHasher> ArcHashableHashSet<V, S> {
+    /// Adds a value, returning whether it was newly added. A shared set is only cloned if the
+    /// value is absent.
+    pub fn insert(&mut self, value: V) -> bool {
+        !self.0.contains(&value) && self.make_mut().insert(value)
+    }
+
+    /// Removes a value, returning whether it was present. A shared set is only cloned if the value
+    /// is present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.0.contains(value) && self.make_mut().remove(value)
+    }
+}
+
+impl<V, S> Clone for ArcHashableHashSet<V, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ArcHashableHashSet(Arc::clone(&self.0))
+    }
+}
+
+impl<V: Debug, S> Debug for ArcHashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V, S: Default> Default for ArcHashableHashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        ArcHashableHashSet(Arc::default())
+    }
+}
+
+impl<V, S> Deref for ArcHashableHashSet<V, S> {
+    type Target = HashableHashSet<V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de, V, S> serde::Deserialize<'de> for ArcHashableHashSet<V, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashSet::deserialize(de).map(ArcHashableHashSet::from)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Eq for ArcHashableHashSet<V, S> {}
+
+impl<V, S> From<HashableHashSet<V, S>> for ArcHashableHashSet<V, S> {
+    #[inline]
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        ArcHashableHashSet(Arc::new(set))
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for ArcHashableHashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        ArcHashableHashSet::from(iter.into_iter().collect::<HashableHashSet<V, S>>())
+    }
+}
+
+impl<V: Hash, S> Hash for ArcHashableHashSet<V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.0.hash(hasher);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a ArcHashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = std::collections::hash_set::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_set::Iter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialEq for ArcHashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl<V: Eq + Hash + serde::Serialize, S: BuildHasher> serde::Serialize for ArcHashableHashSet<V, S> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+/// A [`HashableHashMap`] behind an [`Arc`]. Cloning only bumps a reference count, and the first
+/// mutation of a shared map clones its contents (see [`ArcHashableHashMap::make_mut`]).
+///
+/// See [`ArcHashableHashSet`] for how [`Hash`] and [`Eq`] behave.
+pub struct ArcHashableHashMap<K, V, S = ahash::RandomState>(Arc<HashableHashMap<K, V, S>>);
+
+impl<K, V, S> ArcHashableHashMap<K, V, S> {
+    /// Returns `true` if both maps share the same allocation.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<K: Clone, V: Clone, S: Clone> ArcHashableHashMap<K, V, S> {
+    /// Returns a mutable reference to the map, first cloning it if it is shared.
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut HashableHashMap<K, V, S> {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Unwraps the map, cloning it if it is shared.
+    #[inline]
+    pub fn into_inner(self) -> HashableHashMap<K, V, S> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, S: Clone + BuildHasher> ArcHashableHashMap<K, V, S> {
+    /// Inserts a key-value pair, returning the previous value for the key.
+    #[inline]
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.make_mut().insert(key, value)
+    }
+
+    /// Removes a key, returning its value if it was present. A shared map is only cloned if the
+    /// key is present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        if self.0.contains_key(key) {
+            self.make_mut().remove(key)
+        } else {
+            None
+        }
+    }
+}
+
+impl<K, V, S> Clone for ArcHashableHashMap<K, V, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ArcHashableHashMap(Arc::clone(&self.0))
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for ArcHashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: Default> Default for ArcHashableHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        ArcHashableHashMap(Arc::default())
+    }
+}
+
+impl<K, V, S> Deref for ArcHashableHashMap<K, V, S> {
+    type Target = HashableHashMap<K, V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de, K, V, S> serde::Deserialize<'de> for ArcHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Deserialize<'de>,
+      V: serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashMap::deserialize(de).map(ArcHashableHashMap::from)
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for ArcHashableHashMap<K, V, S> {}
+
+impl<K, V, S> From<HashableHashMap<K, V, S>> for ArcHashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        ArcHashableHashMap(Arc::new(map))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for ArcHashableHashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        ArcHashableHashMap::from(iter.into_iter().collect::<HashableHashMap<K, V, S>>())
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for ArcHashableHashMap<K, V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.0.hash(hasher);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a ArcHashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::collections::hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for ArcHashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl<K, V, S> serde::Serialize for ArcHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Serialize,
+      V: serde::Serialize,
+      S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod arc_test {
+    use crate::{hash, ArcHashableHashMap, ArcHashableHashSet, HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn copy_on_write() {
+        let a: ArcHashableHashSet<u32> = vec![1, 2].into_iter().collect();
+        let mut b = a.clone();
+        assert!(!b.insert(1));
+        assert!(!b.remove(&3));
+        assert!(a.ptr_eq(&b));
+        assert!(b.insert(3));
+        assert!(!a.ptr_eq(&b));
+        assert_eq!(a.into_inner(), HashableHashSet::from([1, 2]));
+
+        let m: ArcHashableHashMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        let mut n = m.clone();
+        assert_eq!(n.remove("b"), None);
+        assert!(m.ptr_eq(&n));
+        assert_eq!(n.insert("a", 2), Some(1));
+        assert_eq!(m.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn hash_matches_inner() {
+        let set = HashableHashSet::from([1, 2, 3]);
+        assert_eq!(hash(&ArcHashableHashSet::from(set.clone())), hash(&set));
+        let map = HashableHashMap::from([("a", 1)]);
+        assert_eq!(hash(&ArcHashableHashMap::from(map.clone())), hash(&map));
+    }
+}
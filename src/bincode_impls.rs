@@ -0,0 +1,80 @@
+//! bincode 2 [`Encode`]/[`Decode`] implementations that share the encoding of the inner std
+//! collections.
+
+use crate::{HashableHashMap, HashableHashSet};
+use bincode::de::{BorrowDecode, BorrowDecoder, Decode, Decoder};
+use bincode::enc::{Encode, Encoder};
+use bincode::error::{DecodeError, EncodeError};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+impl<V: Encode, S> Encode for HashableHashSet<V, S> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<Context, V, S> Decode<Context> for HashableHashSet<V, S>
+where V: Decode<Context> + Eq + Hash,
+      S: BuildHasher + Default,
+{
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        HashSet::decode(decoder).map(HashableHashSet)
+    }
+}
+
+impl<'de, Context, V, S> BorrowDecode<'de, Context> for HashableHashSet<V, S>
+where V: BorrowDecode<'de, Context> + Eq + Hash,
+      S: BuildHasher + Default,
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        HashSet::borrow_decode(decoder).map(HashableHashSet)
+    }
+}
+
+impl<K: Encode, V: Encode, S> Encode for HashableHashMap<K, V, S> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<Context, K, V, S> Decode<Context> for HashableHashMap<K, V, S>
+where K: Decode<Context> + Eq + Hash,
+      V: Decode<Context>,
+      S: BuildHasher + Default,
+{
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        HashMap::decode(decoder).map(HashableHashMap)
+    }
+}
+
+impl<'de, Context, K, V, S> BorrowDecode<'de, Context> for HashableHashMap<K, V, S>
+where K: BorrowDecode<'de, Context> + Eq + Hash,
+      V: BorrowDecode<'de, Context>,
+      S: BuildHasher + Default,
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        HashMap::borrow_decode(decoder).map(HashableHashMap)
+    }
+}
+
+#[cfg(test)]
+mod bincode_impls_test {
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn round_trip() {
+        let mut map: HashableHashMap<String, HashableHashSet<u32>> = HashableHashMap::new();
+        map.insert("key".to_string(), vec![1, 2, 3].into_iter().collect());
+
+        let config = bincode::config::standard();
+        let bytes = bincode::encode_to_vec(&map, config).unwrap();
+        let (decoded, _): (HashableHashMap<String, HashableHashSet<u32>>, _) =
+            bincode::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(decoded, map);
+
+        let (borrowed, _): (HashableHashMap<&str, HashableHashSet<u32>>, _) =
+            bincode::borrow_decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(borrowed["key"], map["key"]);
+    }
+}
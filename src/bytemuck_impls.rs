@@ -0,0 +1,31 @@
+//! [`bytemuck::TransparentWrapper`] implementations, which allow slices and other containers of
+//! std collections to be reinterpreted as the wrappers without copying.
+
+use crate::{HashableHashMap, HashableHashSet};
+use bytemuck::TransparentWrapper;
+use std::collections::{HashMap, HashSet};
+
+// SAFETY: both wrappers are `#[repr(transparent)]` over the std collection and add no invariants.
+unsafe impl<V, S> TransparentWrapper<HashSet<V, S>> for HashableHashSet<V, S> {}
+unsafe impl<K, V, S> TransparentWrapper<HashMap<K, V, S>> for HashableHashMap<K, V, S> {}
+
+#[cfg(test)]
+mod bytemuck_impls_test {
+    use crate::{hash, HashableHashMap, HashableHashSet};
+    use bytemuck::TransparentWrapper;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn wrap_slices() {
+        let sets: Vec<HashSet<u32, ahash::RandomState>> = vec![
+            vec![1, 2].into_iter().collect(),
+            vec![3].into_iter().collect(),
+        ];
+        let wrapped: &[HashableHashSet<u32>] = HashableHashSet::wrap_slice(&sets);
+        assert_eq!(hash(&wrapped[0]), hash(&HashableHashSet::from(sets[0].clone())));
+
+        let mut maps: Vec<HashMap<u32, u32, ahash::RandomState>> = vec![HashMap::default()];
+        HashableHashMap::wrap_slice_mut(&mut maps)[0].insert(1, 1);
+        assert_eq!(maps[0].len(), 1);
+    }
+}
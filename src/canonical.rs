@@ -0,0 +1,243 @@
+//! Canonical byte encodings that do not depend on the hasher or insertion order.
+//!
+//! While an encoding is active on the current thread, the wrappers serialize their entries sorted
+//! by each entry's (or key's) encoded bytes, which is the deterministic ordering described in RFC
+//! 8949 §4.2.1. Because nested wrappers observe the same setting, the whole value is canonical.
+
+use crate::{HashableHashMap, HashableHashSet};
+use serde::ser::{Error, Serialize, Serializer};
+use std::cell::Cell;
+use std::hash::{BuildHasher, Hash};
+
+#[derive(Clone, Copy)]
+pub(crate) enum Encoding {
+    #[cfg(feature = "serde_json")]
+    Json,
+    #[cfg(feature = "ciborium")]
+    Cbor,
+}
+
+thread_local!(static ENCODING: Cell<Option<Encoding>> = const { Cell::new(None) });
+
+/// Returns the canonical encoding in effect for the current thread, if any.
+pub(crate) fn active() -> Option<Encoding> {
+    ENCODING.with(Cell::get)
+}
+
+/// Runs `f` with `encoding` active, restoring the previous setting afterwards (even on panic).
+fn with_encoding<R>(encoding: Encoding, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Encoding>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ENCODING.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(ENCODING.with(|cell| cell.replace(Some(encoding))));
+    f()
+}
+
+impl Encoding {
+    /// Encodes `value` once, keeping the result both as bytes to sort by and in a form that the
+    /// enclosing serializer can write without encoding `value` again. Encoding each entry again
+    /// after sorting would re-encode everything nested below it at every level.
+    fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<Encoded, String> {
+        match self {
+            #[cfg(feature = "serde_json")]
+            Encoding::Json => serde_json::to_string(value)
+                .and_then(serde_json::value::RawValue::from_string)
+                .map(Encoded::Json)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "ciborium")]
+            Encoding::Cbor => {
+                let value = ciborium::Value::serialized(value).map_err(|e| e.to_string())?;
+                let mut bytes = Vec::new();
+                ciborium::into_writer(&value, &mut bytes).map_err(|e| e.to_string())?;
+                Ok(Encoded::Cbor { bytes, value })
+            }
+        }
+    }
+}
+
+/// An encoded entry, which serializes to the same output as the value it was encoded from.
+enum Encoded {
+    #[cfg(feature = "serde_json")]
+    Json(Box<serde_json::value::RawValue>),
+    #[cfg(feature = "ciborium")]
+    Cbor { bytes: Vec<u8>, value: ciborium::Value },
+}
+
+impl Encoded {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            #[cfg(feature = "serde_json")]
+            Encoded::Json(raw) => raw.get().as_bytes(),
+            #[cfg(feature = "ciborium")]
+            Encoded::Cbor { bytes, .. } => bytes,
+        }
+    }
+}
+
+impl Serialize for Encoded {
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        match self {
+            #[cfg(feature = "serde_json")]
+            Encoded::Json(raw) => raw.serialize(ser),
+            #[cfg(feature = "ciborium")]
+            Encoded::Cbor { value, .. } => value.serialize(ser),
+        }
+    }
+}
+
+/// A map key together with its encoding.
+#[cfg_attr(not(feature = "serde_json"), allow(dead_code))]
+struct EncodedKey<'a, K>(&'a K, Encoded);
+
+impl<K: Serialize> Serialize for EncodedKey<'_, K> {
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        match &self.1 {
+            // serde_json does not accept raw values as object keys, but JSON keys are strings or
+            // numbers, which are cheap to encode again.
+            #[cfg(feature = "serde_json")]
+            Encoded::Json(_) => self.0.serialize(ser),
+            #[cfg(feature = "ciborium")]
+            encoded @ Encoded::Cbor { .. } => encoded.serialize(ser),
+        }
+    }
+}
+
+/// Serializes set elements sorted by their encoded bytes.
+pub(crate) fn serialize_seq<'a, V, I, Ser>(encoding: Encoding, iter: I, ser: Ser) -> Result<Ser::Ok, Ser::Error>
+where V: Serialize + 'a,
+      I: Iterator<Item = &'a V>,
+      Ser: Serializer,
+{
+    let mut entries = iter
+        .map(|v| encoding.encode(v))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Ser::Error::custom)?;
+    entries.sort_unstable_by(|e1, e2| e1.bytes().cmp(e2.bytes()));
+    ser.collect_seq(entries)
+}
+
+/// Serializes map entries sorted by their encoded keys.
+pub(crate) fn serialize_map<'a, K, V, I, Ser>(encoding: Encoding, iter: I, ser: Ser) -> Result<Ser::Ok, Ser::Error>
+where K: Serialize + 'a,
+      V: Serialize + 'a,
+      I: Iterator<Item = (&'a K, &'a V)>,
+      Ser: Serializer,
+{
+    let mut entries = iter
+        .map(|(k, v)| encoding.encode(k).map(|encoded| (EncodedKey(k, encoded), v)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Ser::Error::custom)?;
+    entries.sort_unstable_by(|(k1, _), (k2, _)| k1.1.bytes().cmp(k2.1.bytes()));
+    ser.collect_map(entries)
+}
+
+impl<V, S> HashableHashSet<V, S>
+where V: Eq + Hash + Serialize,
+      S: BuildHasher,
+{
+    /// Encodes the set as JSON with elements in canonical order, including within nested
+    /// hashable collections.
+    #[cfg(feature = "serde_json")]
+    pub fn to_canonical_json(&self) -> serde_json::Result<Vec<u8>> {
+        with_encoding(Encoding::Json, || serde_json::to_vec(self))
+    }
+
+    /// Encodes the set as CBOR with elements in canonical order, including within nested
+    /// hashable collections.
+    #[cfg(feature = "ciborium")]
+    pub fn to_canonical_cbor(&self) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+        with_encoding(Encoding::Cbor, || {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(self, &mut bytes).map(|()| bytes)
+        })
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S>
+where K: Eq + Hash + Serialize,
+      V: Serialize,
+      S: BuildHasher,
+{
+    /// Encodes the map as JSON with entries in canonical order, including within nested
+    /// hashable collections.
+    #[cfg(feature = "serde_json")]
+    pub fn to_canonical_json(&self) -> serde_json::Result<Vec<u8>> {
+        with_encoding(Encoding::Json, || serde_json::to_vec(self))
+    }
+
+    /// Encodes the map as CBOR with entries in canonical order, including within nested
+    /// hashable collections.
+    #[cfg(feature = "ciborium")]
+    pub fn to_canonical_cbor(&self) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+        with_encoding(Encoding::Cbor, || {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(self, &mut bytes).map(|()| bytes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod canonical_test {
+    use crate::{HashableHashMap, HashableHashSet};
+
+    fn nested(order: &[u32]) -> HashableHashMap<String, HashableHashSet<u32>> {
+        let mut map = HashableHashMap::new();
+        for &i in order {
+            map.entry(format!("key{}", i % 3)).or_insert_with(HashableHashSet::new).insert(i);
+        }
+        map
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_is_canonical() {
+        let json = nested(&[5, 1, 4, 3, 2, 0]).to_canonical_json().unwrap();
+        assert_eq!(json, nested(&[0, 1, 2, 3, 4, 5]).to_canonical_json().unwrap());
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"{"key0":[0,3],"key1":[1,4],"key2":[2,5]}"#);
+    }
+
+    #[cfg(feature = "ciborium")]
+    #[test]
+    fn cbor_is_canonical() {
+        let cbor = nested(&[5, 1, 4, 3, 2, 0]).to_canonical_cbor().unwrap();
+        assert_eq!(cbor, nested(&[0, 1, 2, 3, 4, 5]).to_canonical_cbor().unwrap());
+    }
+
+    #[cfg(all(feature = "serde_json", feature = "ciborium"))]
+    #[test]
+    fn encodes_nested_values_once() {
+        use serde::{Serialize, Serializer};
+        use std::cell::Cell;
+
+        thread_local!(static SERIALIZED: Cell<usize> = const { Cell::new(0) });
+
+        #[derive(PartialEq, Eq, Hash)]
+        struct Node(u32, HashableHashSet<Node>);
+
+        impl Serialize for Node {
+            fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+                SERIALIZED.with(|count| count.set(count.get() + 1));
+                (self.0, &self.1).serialize(ser)
+            }
+        }
+
+        let mut node = Node(0, HashableHashSet::new());
+        for i in 1..32 {
+            node = Node(i, HashableHashSet::from([node, Node(i + 100, HashableHashSet::new())]));
+        }
+        let root = HashableHashSet::from([node]);
+
+        let json = root.to_canonical_json().unwrap();
+        assert_eq!(SERIALIZED.with(Cell::take), 63);
+        assert!(json.starts_with(b"[[31,[[131,[]],[30,[[130,[]],[29,"));
+        root.to_canonical_cbor().unwrap();
+        assert_eq!(SERIALIZED.with(Cell::take), 63);
+    }
+}
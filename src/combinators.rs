@@ -0,0 +1,566 @@
+//! Bulk operations and combinators that produce new hashable collections. Unless they reuse an
+//! input collection, collections returned here use a clone of the source collection's hasher.
+
+use crate::{HashableHashMap, HashableHashSet};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+impl<V: Eq + Hash, S: BuildHasher> HashableHashSet<V, S> {
+    /// Consumes both sets, returning their union. The larger set's allocation is reused.
+    pub fn union_with(self, other: Self) -> Self {
+        let (mut larger, smaller) = if self.len() >= other.len() { (self, other) } else { (other, self) };
+        larger.extend(smaller);
+        larger
+    }
+
+    /// Consumes both sets, returning their intersection. The smaller set's allocation is reused,
+    /// as the result is no larger than it.
+    pub fn intersection_with(self, other: Self) -> Self {
+        let (mut smaller, larger) = if self.len() <= other.len() { (self, other) } else { (other, self) };
+        smaller.retain(|v| larger.contains(v));
+        smaller
+    }
+
+    /// Consumes the set, returning the elements that are not in `other`.
+    pub fn difference_with<S2: BuildHasher>(mut self, other: &HashableHashSet<V, S2>) -> Self {
+        if other.len() < self.len() {
+            for v in other {
+                self.remove(v);
+            }
+        } else {
+            self.retain(|v| !other.contains(v));
+        }
+        self
+    }
+}
+
+impl<V, S> HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Clone + Default,
+{
+    /// Returns the union of `sets`, starting from a clone of the largest one.
+    pub fn union_many<'a, I>(sets: I) -> Self
+    where I: IntoIterator<Item=&'a Self>,
+          Self: 'a,
+    {
+        let mut sets: Vec<&Self> = sets.into_iter().collect();
+        let largest = match (0..sets.len()).max_by_key(|&i| sets[i].len()) {
+            Some(i) => sets.swap_remove(i),
+            None => return HashableHashSet::default(),
+        };
+        let mut union = largest.clone();
+        for set in sets {
+            union.extend(set.iter().cloned());
+        }
+        union
+    }
+
+    /// Returns the intersection of `sets`, starting from a clone of the smallest one. An empty
+    /// iterator produces an empty set.
+    pub fn intersection_many<'a, I>(sets: I) -> Self
+    where I: IntoIterator<Item=&'a Self>,
+          Self: 'a,
+    {
+        let mut sets: Vec<&Self> = sets.into_iter().collect();
+        sets.sort_unstable_by_key(|set| set.len());
+        let (smallest, rest) = match sets.split_first() {
+            Some(split) => split,
+            None => return HashableHashSet::default(),
+        };
+        let mut intersection = (*smallest).clone();
+        for set in rest {
+            if intersection.is_empty() {
+                break;
+            }
+            intersection.retain(|v| set.contains(v));
+        }
+        intersection
+    }
+}
+
+impl<V, S> HashableHashSet<V, S>
+where V: Eq + Hash,
+      S: BuildHasher + Clone,
+{
+    /// Consumes the set, returning a set of the results of `f` for each element.
+    pub fn map<W: Eq + Hash, F: FnMut(V) -> W>(self, f: F) -> HashableHashSet<W, S> {
+        let mut mapped = HashableHashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        mapped.extend(self.0.into_iter().map(f));
+        mapped
+    }
+
+    /// Consumes the set, returning a set of the elements for which `pred` returns `true`.
+    pub fn filter<F: FnMut(&V) -> bool>(self, pred: F) -> Self {
+        let mut filtered = HashableHashSet::with_hasher(self.hasher().clone());
+        filtered.extend(self.0.into_iter().filter(pred));
+        filtered
+    }
+
+    /// Consumes the set, returning a set of the values for which `f` returns `Some`.
+    pub fn filter_map<W: Eq + Hash, F: FnMut(V) -> Option<W>>(self, f: F) -> HashableHashSet<W, S> {
+        let mut mapped = HashableHashSet::with_hasher(self.hasher().clone());
+        mapped.extend(self.0.into_iter().filter_map(f));
+        mapped
+    }
+
+    /// Consumes the set, splitting it into the elements for which `pred` returns `true` and those
+    /// for which it returns `false`.
+    pub fn partition<F: FnMut(&V) -> bool>(self, mut pred: F) -> (Self, Self) {
+        let mut matching = HashableHashSet::with_hasher(self.hasher().clone());
+        let mut rest = HashableHashSet::with_hasher(self.hasher().clone());
+        for v in self.0 {
+            if pred(&v) {
+                matching.insert(v);
+            } else {
+                rest.insert(v);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Consumes the set, grouping its elements into sets keyed by `key_fn`.
+    pub fn split_by_key<K, F>(self, mut key_fn: F) -> HashableHashMap<K, Self, S>
+    where K: Eq + Hash,
+          F: FnMut(&V) -> K,
+    {
+        let hasher = self.hasher().clone();
+        let mut groups = HashableHashMap::with_hasher(hasher.clone());
+        for v in self.0 {
+            groups.entry(key_fn(&v)).or_insert_with(|| HashableHashSet::with_hasher(hasher.clone())).insert(v);
+        }
+        groups
+    }
+
+    /// Retains only the elements for which `pred` returns `true`, returning the removed elements
+    /// as a new set.
+    pub fn retain_into<F: FnMut(&V) -> bool>(&mut self, mut pred: F) -> Self {
+        let kept = HashableHashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        let removed = HashableHashSet::with_hasher(self.hasher().clone());
+        partition_in_place(self, kept, removed, |v| pred(v))
+    }
+
+    /// Returns the set of all pairs of an element of this set and an element of `other`.
+    pub fn cartesian_product<W, S2>(&self, other: &HashableHashSet<W, S2>) -> HashableHashSet<(V, W), S>
+    where V: Clone,
+          W: Eq + Hash + Clone,
+    {
+        let capacity = self.len().saturating_mul(other.len());
+        let mut product = HashableHashSet::with_capacity_and_hasher(capacity, self.hasher().clone());
+        for v in &self.0 {
+            product.extend(other.iter().map(|w| (v.clone(), w.clone())));
+        }
+        product
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S>
+where K: Eq + Hash,
+      S: BuildHasher + Clone,
+{
+    /// Keeps only the entries whose keys are in `keys`, returning the entries that were removed.
+    pub fn retain_keys<S2: BuildHasher>(&mut self, keys: &HashSet<K, S2>) -> Self {
+        let kept = HashableHashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        let removed = HashableHashMap::with_hasher(self.hasher().clone());
+        partition_in_place(self, kept, removed, |(k, _)| keys.contains(k))
+    }
+
+    /// Returns a map with clones of the entries whose keys are in `keys`.
+    pub fn select_keys<S2: BuildHasher>(&self, keys: &HashSet<K, S2>) -> Self
+    where K: Clone,
+          V: Clone,
+    {
+        let mut selected = HashableHashMap::with_hasher(self.hasher().clone());
+        if keys.len() < self.len() {
+            selected.extend(keys.iter().filter_map(|k| self.0.get_key_value(k)).map(|(k, v)| (k.clone(), v.clone())));
+        } else {
+            selected.extend(self.0.iter().filter(|(k, _)| keys.contains(*k)).map(|(k, v)| (k.clone(), v.clone())));
+        }
+        selected
+    }
+
+    /// Keeps only the entries whose keys are in `keys`, dropping the rest. See
+    /// [`retain_keys`](Self::retain_keys) to get the removed entries back instead.
+    pub fn restrict_to<S2: BuildHasher>(&mut self, keys: &HashSet<K, S2>) {
+        self.0.retain(|k, _| keys.contains(k));
+    }
+
+    /// Removes the entries for `keys`, returning the entries that were present.
+    pub fn remove_keys<'a, Q, I>(&mut self, keys: I) -> Self
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized + 'a,
+          I: IntoIterator<Item=&'a Q>,
+    {
+        let mut removed = HashableHashMap::with_hasher(self.hasher().clone());
+        removed.extend(keys.into_iter().filter_map(|k| self.0.remove_entry(k)));
+        removed
+    }
+
+    /// Returns a set of clones of the keys.
+    pub fn keys_set(&self) -> HashableHashSet<K, S>
+    where K: Clone,
+    {
+        let mut set = HashableHashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        set.extend(self.0.keys().cloned());
+        set
+    }
+
+    /// Consumes the map, returning a set of its keys.
+    pub fn into_keys_set(self) -> HashableHashSet<K, S> {
+        let mut set = HashableHashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        set.extend(self.0.into_keys());
+        set
+    }
+
+    /// Returns a set of clones of the distinct values.
+    pub fn values_set(&self) -> HashableHashSet<V, S>
+    where V: Eq + Hash + Clone,
+    {
+        let mut set = HashableHashSet::with_hasher(self.hasher().clone());
+        set.extend(self.0.values().cloned());
+        set
+    }
+
+    /// Returns a map from each distinct value to the set of keys that map to it.
+    pub fn invert(&self) -> HashableHashMap<V, HashableHashSet<K, S>, S>
+    where K: Clone,
+          V: Eq + Hash + Clone,
+    {
+        let mut inverted = HashableHashMap::with_hasher(self.hasher().clone());
+        for (k, v) in &self.0 {
+            inverted.entry(v.clone())
+                .or_insert_with(|| HashableHashSet::with_hasher(self.hasher().clone()))
+                .insert(k.clone());
+        }
+        inverted
+    }
+
+    /// Consumes the map, returning a map with the same keys and each value transformed by `f`.
+    pub fn map_values<W, F: FnMut(V) -> W>(self, mut f: F) -> HashableHashMap<K, W, S> {
+        let mut mapped = HashableHashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        mapped.extend(self.0.into_iter().map(|(k, v)| (k, f(v))));
+        mapped
+    }
+
+    /// Consumes the map, returning a map with each key transformed by `f`. If several keys map to
+    /// the same new key, an arbitrary one of their values is kept.
+    pub fn map_keys<J: Eq + Hash, F: FnMut(K) -> J>(self, mut f: F) -> HashableHashMap<J, V, S> {
+        let mut mapped = HashableHashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        mapped.extend(self.0.into_iter().map(|(k, v)| (f(k), v)));
+        mapped
+    }
+
+    /// Consumes the map, returning a map of the entries for which `f` returns `Some`, with the
+    /// values it returned.
+    pub fn filter_map_values<W, F: FnMut(V) -> Option<W>>(self, mut f: F) -> HashableHashMap<K, W, S> {
+        let mut mapped = HashableHashMap::with_hasher(self.hasher().clone());
+        mapped.extend(self.0.into_iter().filter_map(|(k, v)| f(v).map(|w| (k, w))));
+        mapped
+    }
+
+    /// Consumes the map, splitting it into the entries for which `pred` returns `true` and those
+    /// for which it returns `false`.
+    pub fn partition<F: FnMut(&K, &V) -> bool>(self, mut pred: F) -> (Self, Self) {
+        let mut matching = HashableHashMap::with_hasher(self.hasher().clone());
+        let mut rest = HashableHashMap::with_hasher(self.hasher().clone());
+        for (k, v) in self.0 {
+            if pred(&k, &v) {
+                matching.insert(k, v);
+            } else {
+                rest.insert(k, v);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Retains only the entries for which `pred` returns `true`, returning the removed entries as
+    /// a new map.
+    pub fn retain_into<F: FnMut(&K, &mut V) -> bool>(&mut self, mut pred: F) -> Self {
+        let kept = HashableHashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        let removed = HashableHashMap::with_hasher(self.hasher().clone());
+        partition_in_place(self, kept, removed, |(k, v)| pred(k, v))
+    }
+
+    /// Returns a map over the keys present in both maps, with values computed by `f` from the
+    /// key and the two values.
+    pub fn zip_with<W, U, S2, F>(&self, other: &HashableHashMap<K, W, S2>, mut f: F) -> HashableHashMap<K, U, S>
+    where K: Clone,
+          S2: BuildHasher,
+          F: FnMut(&K, &V, &W) -> U,
+    {
+        let mut zipped = HashableHashMap::with_hasher(self.hasher().clone());
+        zipped.extend(self.0.iter().filter_map(|(k, v)| {
+            other.get(k).map(|w| (k.clone(), f(k, v, w)))
+        }));
+        zipped
+    }
+
+    /// Returns a map over the keys present in either map, with values computed by `f` from the
+    /// key and the value in each map, if any.
+    pub fn outer_zip_with<W, U, S2, F>(&self, other: &HashableHashMap<K, W, S2>, mut f: F) -> HashableHashMap<K, U, S>
+    where K: Clone,
+          S2: BuildHasher,
+          F: FnMut(&K, Option<&V>, Option<&W>) -> U,
+    {
+        let mut zipped = HashableHashMap::with_hasher(self.hasher().clone());
+        for (k, v) in &self.0 {
+            zipped.insert(k.clone(), f(k, Some(v), other.get(k)));
+        }
+        for (k, w) in other.iter().filter(|(k, _)| !self.0.contains_key(*k)) {
+            zipped.insert(k.clone(), f(k, None, Some(w)));
+        }
+        zipped
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash> HashableHashMap<K, HashableHashSet<V>> {
+    /// Groups `values` into sets keyed by `key_fn`.
+    pub fn from_grouping<I, F>(values: I, mut key_fn: F) -> Self
+    where I: IntoIterator<Item=V>,
+          F: FnMut(&V) -> K,
+    {
+        let mut groups = HashableHashMap::new();
+        for v in values {
+            groups.entry(key_fn(&v)).or_insert_with(HashableHashSet::new).insert(v);
+        }
+        groups
+    }
+}
+
+impl<V: Eq + Hash> HashableHashMap<V, usize> {
+    /// Counts how many times each distinct value occurs in `values`.
+    pub fn counts<I: IntoIterator<Item=V>>(values: I) -> Self {
+        let mut counts = HashableHashMap::new();
+        for v in values {
+            *counts.entry(v).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// Moves each entry of `collection` into `kept` or `removed` according to `keep`, leaving `kept`
+/// in place of `collection` and returning `removed`. If `keep` panics, the entries that have not
+/// been kept yet, including the removed ones, are put back, so none are lost.
+fn partition_in_place<C, F>(collection: &mut C, kept: C, removed: C, mut keep: F) -> C
+where C: IntoIterator + Extend<C::Item>,
+      F: FnMut(&mut C::Item) -> bool,
+{
+    struct Refill<'a, C: IntoIterator + Extend<C::Item>> {
+        kept: &'a mut C,
+        removed: Option<C>,
+        current: Option<C::Item>,
+        rest: C::IntoIter,
+    }
+
+    impl<C: IntoIterator + Extend<C::Item>> Drop for Refill<'_, C> {
+        fn drop(&mut self) {
+            // Only reached with entries left over when `keep` panicked.
+            if let Some(removed) = self.removed.take() {
+                self.kept.extend(removed);
+            }
+            self.kept.extend(self.current.take());
+            self.kept.extend(&mut self.rest);
+        }
+    }
+
+    let all = std::mem::replace(collection, kept);
+    let mut refill = Refill { kept: collection, removed: Some(removed), current: None, rest: all.into_iter() };
+    for entry in refill.rest.by_ref() {
+        let keep = keep(refill.current.insert(entry));
+        let entry = refill.current.take().unwrap();
+        if keep {
+            refill.kept.extend(Some(entry));
+        } else {
+            refill.removed.as_mut().unwrap().extend(Some(entry));
+        }
+    }
+    refill.removed.take().unwrap()
+}
+
+#[cfg(test)]
+mod combinators_test {
+    use super::partition_in_place;
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn owned_set_algebra() {
+        let a = || HashableHashSet::from([1, 2, 3]);
+        let b = || HashableHashSet::from([3, 4]);
+        assert_eq!(a().union_with(b()), HashableHashSet::from([1, 2, 3, 4]));
+        assert_eq!(b().union_with(a()), HashableHashSet::from([1, 2, 3, 4]));
+        assert_eq!(a().intersection_with(b()), HashableHashSet::from([3]));
+        assert_eq!(a().difference_with(&b()), HashableHashSet::from([1, 2]));
+        assert_eq!(b().difference_with(&a()), HashableHashSet::from([4]));
+    }
+
+    #[test]
+    fn union_and_intersection_many() {
+        let sets = vec![
+            HashableHashSet::from([1, 2, 3]),
+            HashableHashSet::from([2, 3, 4, 5]),
+            HashableHashSet::from([3, 2]),
+        ];
+        assert_eq!(HashableHashSet::union_many(&sets), HashableHashSet::from([1, 2, 3, 4, 5]));
+        assert_eq!(HashableHashSet::intersection_many(&sets), HashableHashSet::from([2, 3]));
+        assert!(HashableHashSet::<u32>::union_many(Vec::new()).is_empty());
+        assert!(HashableHashSet::<u32>::intersection_many(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn set_combinators() {
+        let set = HashableHashSet::from([1, 2, 3, 4]);
+        assert_eq!(set.clone().map(|v| v % 2), HashableHashSet::from([0, 1]));
+        assert_eq!(set.clone().filter(|v| *v > 2), HashableHashSet::from([3, 4]));
+        assert_eq!(
+            set.filter_map(|v| if v % 2 == 0 { Some(v.to_string()) } else { None }),
+            HashableHashSet::from(["2".to_string(), "4".to_string()]));
+    }
+
+    #[test]
+    fn from_grouping() {
+        let groups = HashableHashMap::from_grouping(vec!["a", "bb", "cc", "a"], |s| s.len());
+        assert_eq!(groups, HashableHashMap::from([
+            (1, HashableHashSet::from(["a"])),
+            (2, HashableHashSet::from(["bb", "cc"])),
+        ]));
+    }
+
+    #[test]
+    fn counts() {
+        let counts = HashableHashMap::counts("abracadabra".chars());
+        assert_eq!(counts, HashableHashMap::from([('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)]));
+    }
+
+    #[test]
+    fn zip_with() {
+        let prices = HashableHashMap::from([("a", 2.0), ("b", 3.0)]);
+        let quantities = HashableHashMap::from([("b", 4), ("c", 5)]);
+        assert_eq!(
+            prices.zip_with(&quantities, |_, p, q| p * f64::from(*q)),
+            HashableHashMap::from([("b", 12.0)]));
+        assert_eq!(
+            prices.outer_zip_with(&quantities, |_, p, q| (p.is_some(), q.is_some())),
+            HashableHashMap::from([("a", (true, false)), ("b", (true, true)), ("c", (false, true))]));
+    }
+
+    #[test]
+    fn cartesian_product() {
+        let product = HashableHashSet::from([1, 2]).cartesian_product(&HashableHashSet::from(["a", "b"]));
+        assert_eq!(product, HashableHashSet::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]));
+        assert!(HashableHashSet::from([1]).cartesian_product(&HashableHashSet::<u32>::new()).is_empty());
+    }
+
+    #[test]
+    fn partition() {
+        let (even, odd) = HashableHashSet::from([1, 2, 3, 4]).partition(|v| v % 2 == 0);
+        assert_eq!(even, HashableHashSet::from([2, 4]));
+        assert_eq!(odd, HashableHashSet::from([1, 3]));
+
+        let (small, large) = cache().partition(|_, v| *v < 3);
+        assert_eq!(small, HashableHashMap::from([("a", 1), ("b", 2)]));
+        assert_eq!(large, HashableHashMap::from([("c", 3)]));
+    }
+
+    #[test]
+    fn split_by_key() {
+        let groups = HashableHashSet::from(["a", "bb", "cc"]).split_by_key(|s| s.len());
+        assert_eq!(groups, HashableHashMap::from([
+            (1, HashableHashSet::from(["a"])),
+            (2, HashableHashSet::from(["bb", "cc"])),
+        ]));
+    }
+
+    #[test]
+    fn retain_into() {
+        let mut set = HashableHashSet::from([1, 2, 3, 4]);
+        assert_eq!(set.retain_into(|v| v % 2 == 0), HashableHashSet::from([1, 3]));
+        assert_eq!(set, HashableHashSet::from([2, 4]));
+
+        let mut map = cache();
+        assert_eq!(map.retain_into(|_, v| *v > 1), HashableHashMap::from([("a", 1)]));
+        assert_eq!(map, HashableHashMap::from([("b", 2), ("c", 3)]));
+    }
+
+    fn cache() -> HashableHashMap<&'static str, u32> {
+        HashableHashMap::from([("a", 1), ("b", 2), ("c", 3)])
+    }
+
+    #[test]
+    fn retain_keys() {
+        let mut map = cache();
+        let evicted = map.retain_keys(&HashableHashSet::from(["a", "z"]));
+        assert_eq!(map, HashableHashMap::from([("a", 1)]));
+        assert_eq!(evicted, HashableHashMap::from([("b", 2), ("c", 3)]));
+    }
+
+    #[test]
+    fn select_keys() {
+        let mut map = cache();
+        let keys = HashableHashSet::from(["a", "c", "z"]);
+        assert_eq!(map.select_keys(&keys), HashableHashMap::from([("a", 1), ("c", 3)]));
+        assert_eq!(map.select_keys(&HashableHashSet::from(["b"])), HashableHashMap::from([("b", 2)]));
+        map.restrict_to(&keys);
+        assert_eq!(map, HashableHashMap::from([("a", 1), ("c", 3)]));
+    }
+
+    #[test]
+    fn remove_keys() {
+        let mut map = cache();
+        let removed = map.remove_keys(&["a", "z"]);
+        assert_eq!(map, HashableHashMap::from([("b", 2), ("c", 3)]));
+        assert_eq!(removed, HashableHashMap::from([("a", 1)]));
+    }
+
+    #[test]
+    fn keys_and_values_sets() {
+        let map = HashableHashMap::from([("a", 1), ("b", 2), ("c", 1)]);
+        assert_eq!(map.keys_set(), HashableHashSet::from(["a", "b", "c"]));
+        assert_eq!(map.values_set(), HashableHashSet::from([1, 2]));
+        assert_eq!(map.into_keys_set(), HashableHashSet::from(["a", "b", "c"]));
+    }
+
+    #[test]
+    fn invert() {
+        let map = HashableHashMap::from([("a", 1), ("b", 2), ("c", 1)]);
+        assert_eq!(map.invert(), HashableHashMap::from([
+            (1, HashableHashSet::from(["a", "c"])),
+            (2, HashableHashSet::from(["b"])),
+        ]));
+    }
+
+    #[test]
+    fn map_combinators() {
+        let map = HashableHashMap::from([("a", 1), ("bb", 2)]);
+        assert_eq!(map.clone().map_values(|v| v * 10), HashableHashMap::from([("a", 10), ("bb", 20)]));
+        assert_eq!(map.clone().map_keys(str::len), HashableHashMap::from([(1, 1), (2, 2)]));
+        assert_eq!(
+            map.filter_map_values(|v| if v > 1 { Some(v.to_string()) } else { None }),
+            HashableHashMap::from([("bb", "2".to_string())]));
+    }
+
+    #[test]
+    fn partition_in_place_keeps_entries_on_panic() {
+        let mut map = HashableHashMap::from([(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+        let (kept, removed) = (HashableHashMap::new(), HashableHashMap::new());
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            partition_in_place(&mut map, kept, removed, |_| {
+                calls += 1;
+                assert!(calls < 3);
+                calls % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(map, HashableHashMap::from([(1, "a"), (2, "b"), (3, "c"), (4, "d")]));
+    }
+
+    #[test]
+    fn retain_into_keeps_elements_on_panic() {
+        let mut set = HashableHashSet::from([1, 2, 3, 4]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            set.retain_into(|&v| if v == 3 { panic!() } else { v % 2 == 0 })
+        }));
+        assert!(result.is_err());
+        assert_eq!(set, HashableHashSet::from([1, 2, 3, 4]));
+    }
+}
@@ -0,0 +1,229 @@
+//! Helpers for maps whose values are counts, such as frequency tables and reference counts.
+
+use crate::HashableHashMap;
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Add, Sub};
+
+/// An integer type usable as the value of a counting [`HashableHashMap`].
+pub trait Count: Copy + Eq + Add<Output=Self> + Sub<Output=Self> {
+    /// The additive identity.
+    const ZERO: Self;
+    /// The unit increment.
+    const ONE: Self;
+
+    /// Adds, returning `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    /// Subtracts, returning `None` on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    /// Adds, clamping at the numeric bounds.
+    fn saturating_add(self, other: Self) -> Self;
+    /// Subtracts, clamping at the numeric bounds.
+    fn saturating_sub(self, other: Self) -> Self;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {
+        $(
+            impl Count for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                #[inline]
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                #[inline]
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::saturating_add(self, other)
+                }
+
+                #[inline]
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$t>::saturating_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_count!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<K: Eq + Hash, N: Count, S: BuildHasher> HashableHashMap<K, N, S> {
+    /// Adds one to the count for `key`, inserting it if absent. Returns the new count.
+    #[inline]
+    pub fn increment(&mut self, key: K) -> N {
+        self.add(key, N::ONE)
+    }
+
+    /// Adds `n` to the count for `key`, inserting it if absent. Returns the new count.
+    pub fn add(&mut self, key: K, n: N) -> N {
+        let count = self.0.entry(key).or_insert(N::ZERO);
+        *count = *count + n;
+        *count
+    }
+
+    /// Subtracts one from the count for `key`, removing the key once its count reaches zero.
+    /// Returns the new count, or `None` if the key was absent.
+    pub fn decrement_and_remove_at_zero<Q>(&mut self, key: &Q) -> Option<N>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        let count = self.0.get_mut(key)?;
+        if *count == N::ONE || *count == N::ZERO {
+            self.0.remove(key);
+            return Some(N::ZERO);
+        }
+        *count = *count - N::ONE;
+        Some(*count)
+    }
+}
+
+impl<K: Eq + Hash + Clone, N: Count, S: BuildHasher> HashableHashMap<K, N, S> {
+    /// Adds each count in `other` to the count for the same key in this map, treating absent keys
+    /// as zero.
+    pub fn add_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) {
+        self.combine_map(other, |a, b| a + b);
+    }
+
+    /// Subtracts each count in `other` from the count for the same key in this map, treating
+    /// absent keys as zero. Keys whose count becomes zero are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics on overflow when overflow checks are enabled, as in debug builds, and wraps
+    /// otherwise. With an unsigned `N`, this happens whenever a count in `other` exceeds the count
+    /// in this map, including a nonzero count for a key that is absent here. Use
+    /// [`saturating_sub_assign_map`](Self::saturating_sub_assign_map) or
+    /// [`checked_sub_assign_map`](Self::checked_sub_assign_map) when that can happen.
+    pub fn sub_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) {
+        self.combine_map(other, |a, b| a - b);
+    }
+
+    /// Like [`add_assign_map`](Self::add_assign_map), but clamps at the numeric bounds.
+    pub fn saturating_add_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) {
+        self.combine_map(other, N::saturating_add);
+    }
+
+    /// Like [`sub_assign_map`](Self::sub_assign_map), but clamps at the numeric bounds.
+    pub fn saturating_sub_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) {
+        self.combine_map(other, N::saturating_sub);
+    }
+
+    /// Like [`add_assign_map`](Self::add_assign_map), but leaves this map unchanged and returns
+    /// the first key found to overflow.
+    pub fn checked_add_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) -> Result<(), K> {
+        self.checked_combine_map(other, N::checked_add)
+    }
+
+    /// Like [`sub_assign_map`](Self::sub_assign_map), but leaves this map unchanged and returns
+    /// the first key found to overflow.
+    pub fn checked_sub_assign_map<S2>(&mut self, other: &HashableHashMap<K, N, S2>) -> Result<(), K> {
+        self.checked_combine_map(other, N::checked_sub)
+    }
+
+    fn combine_map<S2, F: Fn(N, N) -> N>(&mut self, other: &HashableHashMap<K, N, S2>, op: F) {
+        for (k, &n) in other.iter() {
+            match self.0.get_mut(k) {
+                Some(count) => *count = op(*count, n),
+                None => {
+                    self.0.insert(k.clone(), op(N::ZERO, n));
+                }
+            }
+        }
+    }
+
+    fn checked_combine_map<S2, F>(&mut self, other: &HashableHashMap<K, N, S2>, op: F) -> Result<(), K>
+    where F: Fn(N, N) -> Option<N>,
+    {
+        // Check every key before writing so that an overflow leaves the map untouched.
+        let mut results = Vec::with_capacity(other.len());
+        for (k, &n) in other.iter() {
+            let count = self.0.get(k).copied().unwrap_or(N::ZERO);
+            match op(count, n) {
+                Some(result) => results.push((k, result)),
+                None => return Err(k.clone()),
+            }
+        }
+        for (k, result) in results {
+            match self.0.get_mut(k) {
+                Some(count) => *count = result,
+                None => {
+                    self.0.insert(k.clone(), result);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod counter_test {
+    use crate::HashableHashMap;
+
+    #[test]
+    fn increment_and_add() {
+        let mut counts: HashableHashMap<&str, u32> = HashableHashMap::new();
+        assert_eq!(counts.increment("a"), 1);
+        assert_eq!(counts.increment("a"), 2);
+        assert_eq!(counts.add("b", 5), 5);
+        assert_eq!(counts, HashableHashMap::from([("a", 2), ("b", 5)]));
+    }
+
+    #[test]
+    fn decrement_and_remove_at_zero() {
+        let mut refs = HashableHashMap::from([("a", 2i64)]);
+        assert_eq!(refs.decrement_and_remove_at_zero("a"), Some(1));
+        assert_eq!(refs.decrement_and_remove_at_zero("a"), Some(0));
+        assert!(refs.is_empty());
+        assert_eq!(refs.decrement_and_remove_at_zero("a"), None);
+    }
+
+    #[test]
+    fn arithmetic_merge() {
+        let mut total = HashableHashMap::from([("a", 1u8), ("b", 2)]);
+        total.add_assign_map(&HashableHashMap::from([("b", 3), ("c", 4)]));
+        assert_eq!(total, HashableHashMap::from([("a", 1), ("b", 5), ("c", 4)]));
+        total.sub_assign_map(&HashableHashMap::from([("a", 1)]));
+        assert_eq!(total, HashableHashMap::from([("a", 0), ("b", 5), ("c", 4)]));
+
+        total.saturating_sub_assign_map(&HashableHashMap::from([("b", 9)]));
+        assert_eq!(total[&"b"], 0);
+        total.saturating_add_assign_map(&HashableHashMap::from([("c", 255)]));
+        assert_eq!(total[&"c"], 255);
+
+        let before = total.clone();
+        assert_eq!(total.checked_add_assign_map(&HashableHashMap::from([("a", 1), ("c", 1)])), Err("c"));
+        assert_eq!(total, before);
+        assert_eq!(total.checked_sub_assign_map(&HashableHashMap::from([("c", 5)])), Ok(()));
+        assert_eq!(total[&"c"], 250);
+    }
+
+    #[test]
+    fn sub_absent_key() {
+        let other = HashableHashMap::from([("a", 1u32)]);
+        let mut counts: HashableHashMap<&str, u32> = HashableHashMap::new();
+        assert_eq!(counts.checked_sub_assign_map(&other), Err("a"));
+        assert!(counts.is_empty());
+        counts.saturating_sub_assign_map(&other);
+        assert_eq!(counts, HashableHashMap::from([("a", 0)]));
+
+        let mut signed: HashableHashMap<&str, i32> = HashableHashMap::new();
+        signed.sub_assign_map(&HashableHashMap::from([("a", 1)]));
+        assert_eq!(signed, HashableHashMap::from([("a", -1)]));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn sub_absent_key_overflows() {
+        let mut counts: HashableHashMap<&str, u32> = HashableHashMap::new();
+        counts.sub_assign_map(&HashableHashMap::from([("a", 1)]));
+    }
+}
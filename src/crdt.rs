@@ -0,0 +1,288 @@
+//! Conflict-free replicated sets whose states are hashable.
+//!
+//! Each replica updates its own copy and periodically merges in the copies of the others. Because
+//! `merge` is commutative, associative, and idempotent, replicas that have seen the same updates
+//! end up in equal states, which can be checked cheaply by comparing hashes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::crdt::OrSet;
+//!
+//! let mut alice = OrSet::new(1);
+//! let mut bob = OrSet::new(2);
+//! alice.insert("x");
+//! bob.merge(&alice);
+//! bob.remove(&"x");
+//! alice.insert("x"); // concurrent with Bob's removal, so it survives
+//! alice.merge(&bob);
+//! bob.merge(&alice);
+//! assert!(alice.contains(&"x"));
+//! assert_eq!(alice, bob);
+//! ```
+
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+
+/// A grow-only set, whose merge is the union of the two sets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+#[serde(bound(
+    serialize = "V: Eq + Hash + Serialize",
+    deserialize = "V: Eq + Hash + Deserialize<'de>"))]
+pub struct GSet<V> {
+    elements: HashableHashSet<V>,
+}
+
+impl<V> GSet<V> {
+    #[inline]
+    pub fn new() -> GSet<V> {
+        GSet { elements: HashableHashSet::new() }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Iterates over the elements in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> std::collections::hash_set::Iter<'_, V> {
+        self.elements.iter()
+    }
+
+    /// Returns the elements as a set.
+    #[inline]
+    pub fn as_set(&self) -> &HashableHashSet<V> {
+        &self.elements
+    }
+}
+
+impl<V: Eq + Hash> GSet<V> {
+    /// Adds a value, returning whether it was newly added.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> bool {
+        self.elements.insert(value)
+    }
+
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.elements.contains(value)
+    }
+
+    /// Adds the elements of another replica.
+    pub fn merge(&mut self, other: &GSet<V>)
+    where V: Clone,
+    {
+        self.elements.extend(other.elements.iter().cloned());
+    }
+}
+
+impl<V> Default for GSet<V> {
+    #[inline]
+    fn default() -> Self {
+        GSet::new()
+    }
+}
+
+impl<V: Eq + Hash> Eq for GSet<V> {}
+
+impl<V: Hash> Hash for GSet<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.elements.hash(state);
+    }
+}
+
+impl<V: Eq + Hash> PartialEq for GSet<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.elements == other.elements
+    }
+}
+
+/// Identifies a single insertion: the inserting replica and its count of insertions so far.
+type Tag = (u64, u64);
+
+/// An observed-remove set. A removal only cancels the insertions its replica has observed, so an
+/// insertion concurrent with a removal of the same value wins.
+///
+/// Each replica must be created with a distinct replica ID. The state keeps the tags of removed
+/// insertions, so it grows with the number of removals.
+///
+/// [`Hash`] and [`Eq`] consider the replicated state but not the replica ID or its insertion
+/// count, so converged replicas compare equal.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "V: Eq + Hash + Serialize",
+    deserialize = "V: Eq + Hash + Deserialize<'de>"))]
+pub struct OrSet<V> {
+    replica: u64,
+    counter: u64,
+    // Invariant: holds no removed tags and no empty sets.
+    live: HashableHashMap<V, HashableHashSet<Tag>>,
+    removed: HashableHashSet<Tag>,
+}
+
+impl<V> OrSet<V> {
+    /// Creates an empty set for the replica with the given ID.
+    pub fn new(replica: u64) -> OrSet<V> {
+        OrSet { replica, counter: 0, live: HashableHashMap::new(), removed: HashableHashSet::new() }
+    }
+
+    /// Returns the ID of the replica that owns this copy.
+    #[inline]
+    pub fn replica(&self) -> u64 {
+        self.replica
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// Iterates over the elements in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item=&V> {
+        self.live.keys()
+    }
+}
+
+impl<V: Eq + Hash> OrSet<V> {
+    /// Adds a value, returning whether it was newly added.
+    pub fn insert(&mut self, value: V) -> bool {
+        self.counter += 1;
+        let tags = self.live.entry(value).or_default();
+        tags.insert((self.replica, self.counter));
+        tags.len() == 1
+    }
+
+    /// Removes a value, cancelling the insertions of it observed so far. Returns whether it was
+    /// present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        match self.live.remove(value) {
+            Some(tags) => {
+                self.removed.extend(tags);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.live.contains_key(value)
+    }
+
+    /// Incorporates the insertions and removals of another replica.
+    pub fn merge(&mut self, other: &OrSet<V>)
+    where V: Clone,
+    {
+        self.removed.extend(other.removed.iter().copied());
+        for (value, tags) in other.live.iter() {
+            self.live.entry(value.clone()).or_default().extend(tags.iter().copied());
+        }
+        let removed = &self.removed;
+        self.live.retain(|_, tags| {
+            tags.retain(|tag| !removed.contains(tag));
+            !tags.is_empty()
+        });
+    }
+
+    /// Returns the elements as a set.
+    pub fn to_set(&self) -> HashableHashSet<V>
+    where V: Clone,
+    {
+        self.live.keys().cloned().collect()
+    }
+}
+
+impl<V: Eq + Hash> Eq for OrSet<V> {}
+
+impl<V: Hash> Hash for OrSet<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.live.hash(state);
+        self.removed.hash(state);
+    }
+}
+
+impl<V: Eq + Hash> PartialEq for OrSet<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.live == other.live && self.removed == other.removed
+    }
+}
+
+#[cfg(test)]
+mod crdt_test {
+    use super::{GSet, OrSet};
+    use crate::{hash, HashableHashSet};
+
+    #[test]
+    fn merge_is_commutative_and_idempotent() {
+        let mut a = OrSet::new(1);
+        let mut b = OrSet::new(2);
+        a.insert(1);
+        a.insert(2);
+        b.insert(2);
+        b.merge(&a);
+        b.remove(&1);
+        a.insert(3);
+
+        let mut ab = a.clone();
+        ab.merge(&b);
+        let mut ba = b.clone();
+        ba.merge(&a);
+        assert_eq!(ab, ba);
+        assert_eq!(hash(&ab), hash(&ba));
+        assert_eq!(ab.to_set(), HashableHashSet::from([2, 3]));
+
+        let before = ab.clone();
+        ab.merge(&ba);
+        assert_eq!(ab, before);
+
+        let mut g = GSet::new();
+        g.insert("x");
+        let mut h = GSet::new();
+        h.insert("y");
+        h.merge(&g);
+        g.merge(&h);
+        assert_eq!(g, h);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut set = OrSet::new(7);
+        set.insert("a".to_string());
+        set.insert("b".to_string());
+        assert!(set.remove("a"));
+        assert!(!set.contains("a") && set.contains("b"));
+        let json = serde_json::to_string(&set).unwrap();
+        let mut restored: OrSet<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, set);
+        restored.insert("c".to_string());
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.replica(), 7);
+
+        let mut grow_only = GSet::new();
+        grow_only.insert("a".to_string());
+        assert!(grow_only.contains("a"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Hashable wrappers around [`dashmap`]'s concurrent collections.
+//!
+//! [`Hash`] read-locks every shard before reading any of them, so the result reflects a single
+//! consistent snapshot even while other threads are inserting. Like other operations that lock
+//! several shards, hashing can deadlock if the calling thread already holds a reference into the
+//! collection. Equality, in contrast, locks one shard at a time, so compare only collections that
+//! are not being modified.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::dashmap::HashableDashSet;
+//! use std::collections::HashMap;
+//!
+//! let results: HashableDashSet<u32> = HashableDashSet::new();
+//! std::thread::scope(|scope| {
+//!     for i in 0..4 {
+//!         let results = &results;
+//!         scope.spawn(move || results.insert(i));
+//!     }
+//! });
+//! let mut memo = HashMap::new();
+//! memo.insert(results, "done");
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::dashmap::{DashMap, DashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`DashSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet`].
+#[repr(transparent)]
+pub struct HashableDashSet<V, S = ahash::RandomState>(DashSet<V, S>);
+
+impl<V: Eq + Hash> HashableDashSet<V> {
+    #[inline]
+    pub fn new() -> HashableDashSet<V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableDashSet<V> {
+        HashableDashSet::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> HashableDashSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableDashSet(DashSet::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableDashSet(DashSet::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<V, S> HashableDashSet<V, S> {
+    /// Consumes the wrapper, returning the inner [`DashSet`].
+    #[inline]
+    pub fn into_inner(self) -> DashSet<V, S> {
+        self.0
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: Clone> Clone for HashableDashSet<V, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        HashableDashSet(self.0.clone())
+    }
+}
+
+impl<V: Debug + Eq + Hash, S: BuildHasher + Clone> Debug for HashableDashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone + Default> Default for HashableDashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableDashSet(DashSet::default())
+    }
+}
+
+impl<V, S> Deref for HashableDashSet<V, S> {
+    type Target = DashSet<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, S> DerefMut for HashableDashSet<V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> Eq for HashableDashSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> Extend<V> for HashableDashSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V, S> From<DashSet<V, S>> for HashableDashSet<V, S> {
+    #[inline]
+    fn from(set: DashSet<V, S>) -> Self {
+        HashableDashSet(set)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone + Default> FromIterator<V> for HashableDashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableDashSet(DashSet::from_iter(iter))
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> Hash for HashableDashSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        let guards: Vec<_> = self.0.shards().iter().map(|shard| shard.read()).collect();
+        // SAFETY: The read guards outlive the iterators and buckets, and prevent concurrent writes
+        // to the tables while they are read.
+        let prehashes = guards.iter()
+            .flat_map(|table| unsafe { table.iter() })
+            .map(|bucket| prehash(unsafe { &bucket.as_ref().0 }));
+        hash_prehashes(prehashes, hasher);
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> IntoIterator for HashableDashSet<V, S> {
+    type Item = V;
+    type IntoIter = ::dashmap::iter_set::OwningIter<V, S>;
+
+    #[inline]
+    fn into_iter(self) -> ::dashmap::iter_set::OwningIter<V, S> {
+        self.0.into_iter()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Clone> PartialEq for HashableDashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+            || self.0.len() == other.0.len() && self.0.iter().all(|v| other.0.contains(v.key()))
+    }
+}
+
+/// A [`DashMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[repr(transparent)]
+pub struct HashableDashMap<K, V, S = ahash::RandomState>(DashMap<K, V, S>);
+
+impl<K: Eq + Hash, V> HashableDashMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableDashMap<K, V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableDashMap<K, V> {
+        HashableDashMap::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> HashableDashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableDashMap(DashMap::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableDashMap(DashMap::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<K, V, S> HashableDashMap<K, V, S> {
+    /// Consumes the wrapper, returning the inner [`DashMap`].
+    #[inline]
+    pub fn into_inner(self) -> DashMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: Clone> Clone for HashableDashMap<K, V, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        HashableDashMap(self.0.clone())
+    }
+}
+
+impl<K: Debug + Eq + Hash, V: Debug, S: BuildHasher + Clone> Debug for HashableDashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone + Default> Default for HashableDashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableDashMap(DashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for HashableDashMap<K, V, S> {
+    type Target = DashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableDashMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher + Clone> Eq for HashableDashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> Extend<(K, V)> for HashableDashMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> From<DashMap<K, V, S>> for HashableDashMap<K, V, S> {
+    #[inline]
+    fn from(map: DashMap<K, V, S>) -> Self {
+        HashableDashMap(map)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone + Default> FromIterator<(K, V)> for HashableDashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableDashMap(DashMap::from_iter(iter))
+    }
+}
+
+impl<K: Eq + Hash, V: Hash, S: BuildHasher + Clone> Hash for HashableDashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let guards: Vec<_> = self.0.shards().iter().map(|shard| shard.read()).collect();
+        // SAFETY: See `HashableDashSet`'s implementation.
+        let prehashes = guards.iter()
+            .flat_map(|table| unsafe { table.iter() })
+            .map(|bucket| {
+                let (k, v) = unsafe { bucket.as_ref() };
+                prehash(&(k, v.get()))
+            });
+        hash_prehashes(prehashes, state);
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> IntoIterator for HashableDashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = ::dashmap::iter::OwningIter<K, V, S>;
+
+    #[inline]
+    fn into_iter(self) -> ::dashmap::iter::OwningIter<K, V, S> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher + Clone> PartialEq for HashableDashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+            || self.0.len() == other.0.len()
+                && self.0.iter().all(|entry| other.0.get(entry.key()).is_some_and(|v| *v == *entry.value()))
+    }
+}
+
+#[cfg(test)]
+mod dashmap_test {
+    use super::{HashableDashMap, HashableDashSet};
+    use crate::hash;
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let set: HashableDashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let std_set: crate::HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std_set));
+
+        let map: HashableDashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std_map));
+    }
+
+    #[test]
+    fn concurrent_accumulation() {
+        let map: HashableDashMap<u32, u32> = HashableDashMap::new();
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let map = &map;
+                scope.spawn(move || map.insert(i, i * i));
+            }
+        });
+        let expected: HashableDashMap<u32, u32> = (0..8).map(|i| (i, i * i)).collect();
+        assert_eq!(map, expected);
+        assert_eq!(hash(&map), hash(&expected));
+    }
+}
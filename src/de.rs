@@ -0,0 +1,321 @@
+//! Configurable deserialization via [`DeserializeSeed`].
+//!
+//! The [`Deserialize`] implementation of [`HashableHashMap`] silently keeps the last value when a
+//! key repeats, mirroring [`HashMap`](std::collections::HashMap). [`MapSeed`] lets callers choose a
+//! different [`DuplicateKeys`] policy or merge the values instead. Both [`MapSeed`] and
+//! [`SetSeed`] also accept a capacity hint and a [`BuildHasher`] instance, so large collections can
+//! be preallocated and use existing hasher state.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::HashableHashMap;
+//! use hashable::de::{DuplicateKeys, MapSeed};
+//! use serde::de::DeserializeSeed;
+//!
+//! let mut de = serde_json::Deserializer::from_str(r#"{"a": 1, "a": 2}"#);
+//! let result = MapSeed::<String, u32>::new()
+//!     .on_duplicate(DuplicateKeys::Error)
+//!     .deserialize(&mut de);
+//! assert!(result.is_err());
+//! ```
+
+use crate::{HashableHashMap, HashableHashSet};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt::{self, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+/// What to do when a map key appears more than once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DuplicateKeys {
+    /// Fail deserialization.
+    Error,
+    /// Keep the value that appeared first.
+    FirstWins,
+    /// Keep the value that appeared last. This is the behavior of the `Deserialize` impl.
+    LastWins,
+}
+
+enum Policy<F> {
+    Keys(DuplicateKeys),
+    Merge(F),
+}
+
+/// Upper bound on how much a serializer's size hint may preallocate, as the hint is untrusted.
+const MAX_PREALLOCATED: usize = 4096;
+
+fn capacity_for(requested: usize, hint: Option<usize>) -> usize {
+    requested.max(hint.unwrap_or(0).min(MAX_PREALLOCATED))
+}
+
+/// A [`DeserializeSeed`] that builds a [`HashableHashMap`], handling duplicate keys as configured.
+pub struct MapSeed<K, V, S = ahash::RandomState, F = fn(&K, &mut V, V)> {
+    capacity: usize,
+    hasher: S,
+    policy: Policy<F>,
+    marker: PhantomData<HashableHashMap<K, V>>,
+}
+
+impl<K, V, S: Default> MapSeed<K, V, S> {
+    /// Creates a seed that keeps the last value for duplicate keys.
+    #[inline]
+    pub fn new() -> Self {
+        MapSeed::with_hasher(S::default())
+    }
+
+    /// Creates a seed that preallocates room for at least `capacity` entries.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        MapSeed::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<K, V, S> MapSeed<K, V, S> {
+    /// Creates a seed whose map uses `hasher`.
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        MapSeed::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// Creates a seed whose map uses `hasher` and preallocates room for at least `capacity`
+    /// entries.
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        MapSeed {
+            capacity,
+            hasher,
+            policy: Policy::Keys(DuplicateKeys::LastWins),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S: Default> Default for MapSeed<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        MapSeed::new()
+    }
+}
+
+impl<K, V, S, F> MapSeed<K, V, S, F> {
+    /// Handles duplicate keys according to `policy`.
+    pub fn on_duplicate(self, policy: DuplicateKeys) -> Self {
+        MapSeed {
+            policy: Policy::Keys(policy),
+            ..self
+        }
+    }
+
+    /// Merges the value of a duplicate key into the value already deserialized for that key.
+    pub fn merge_duplicates<G: FnMut(&K, &mut V, V)>(self, merge: G) -> MapSeed<K, V, S, G> {
+        MapSeed {
+            capacity: self.capacity,
+            hasher: self.hasher,
+            policy: Policy::Merge(merge),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, K, V, S, F> DeserializeSeed<'de> for MapSeed<K, V, S, F>
+where K: Eq + Hash + Deserialize<'de>,
+      V: Deserialize<'de>,
+      S: BuildHasher,
+      F: FnMut(&K, &mut V, V),
+{
+    type Value = HashableHashMap<K, V, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_map(self)
+    }
+}
+
+impl<'de, K, V, S, F> Visitor<'de> for MapSeed<K, V, S, F>
+where K: Eq + Hash + Deserialize<'de>,
+      V: Deserialize<'de>,
+      S: BuildHasher,
+      F: FnMut(&K, &mut V, V),
+{
+    type Value = HashableHashMap<K, V, S>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut access: A) -> Result<Self::Value, A::Error> {
+        let capacity = capacity_for(self.capacity, access.size_hint());
+        let mut map = HashableHashMap::with_capacity_and_hasher(capacity, self.hasher);
+        while let Some((k, v)) = access.next_entry()? {
+            let existing = match map.get_mut(&k) {
+                Some(existing) => existing,
+                None => {
+                    map.insert(k, v);
+                    continue;
+                }
+            };
+            match &mut self.policy {
+                Policy::Keys(DuplicateKeys::Error) => {
+                    return Err(de::Error::custom("duplicate map key"));
+                }
+                Policy::Keys(DuplicateKeys::FirstWins) => {}
+                Policy::Keys(DuplicateKeys::LastWins) => *existing = v,
+                Policy::Merge(merge) => merge(&k, existing, v),
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// A [`DeserializeSeed`] that builds a [`HashableHashSet`] with a given capacity and hasher.
+pub struct SetSeed<V, S = ahash::RandomState> {
+    capacity: usize,
+    hasher: S,
+    marker: PhantomData<HashableHashSet<V>>,
+}
+
+impl<V, S: Default> SetSeed<V, S> {
+    /// Creates a seed that uses the default hasher.
+    #[inline]
+    pub fn new() -> Self {
+        SetSeed::with_hasher(S::default())
+    }
+
+    /// Creates a seed that preallocates room for at least `capacity` elements.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SetSeed::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<V, S> SetSeed<V, S> {
+    /// Creates a seed whose set uses `hasher`.
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        SetSeed::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// Creates a seed whose set uses `hasher` and preallocates room for at least `capacity`
+    /// elements.
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        SetSeed {
+            capacity,
+            hasher,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<V, S: Default> Default for SetSeed<V, S> {
+    #[inline]
+    fn default() -> Self {
+        SetSeed::new()
+    }
+}
+
+impl<'de, V, S> DeserializeSeed<'de> for SetSeed<V, S>
+where V: Eq + Hash + Deserialize<'de>,
+      S: BuildHasher,
+{
+    type Value = HashableHashSet<V, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_seq(self)
+    }
+}
+
+impl<'de, V, S> Visitor<'de> for SetSeed<V, S>
+where V: Eq + Hash + Deserialize<'de>,
+      S: BuildHasher,
+{
+    type Value = HashableHashSet<V, S>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let capacity = capacity_for(self.capacity, access.size_hint());
+        let mut set = HashableHashSet::with_capacity_and_hasher(capacity, self.hasher);
+        while let Some(v) = access.next_element()? {
+            set.insert(v);
+        }
+        Ok(set)
+    }
+}
+
+/// Deserializes a [`HashableHashMap`], failing if any key appears more than once. Intended for
+/// `#[serde(deserialize_with = "hashable::de::deny_duplicates")]`.
+pub fn deny_duplicates<'de, D, K, V, S>(de: D) -> Result<HashableHashMap<K, V, S>, D::Error>
+where D: Deserializer<'de>,
+      K: Eq + Hash + Deserialize<'de>,
+      V: Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    MapSeed::new().on_duplicate(DuplicateKeys::Error).deserialize(de)
+}
+
+#[cfg(test)]
+mod de_test {
+    use super::{DuplicateKeys, MapSeed, SetSeed};
+    use crate::{HashableHashMap, HashableHashSet};
+    use serde::de::DeserializeSeed;
+
+    const JSON: &str = r#"{"a": 1, "b": 2, "a": 3}"#;
+
+    fn deserialize(policy: DuplicateKeys) -> serde_json::Result<HashableHashMap<String, u32>> {
+        let mut de = serde_json::Deserializer::from_str(JSON);
+        MapSeed::new().on_duplicate(policy).deserialize(&mut de)
+    }
+
+    #[test]
+    fn policies() {
+        assert!(deserialize(DuplicateKeys::Error).is_err());
+        assert_eq!(deserialize(DuplicateKeys::FirstWins).unwrap()["a"], 1);
+        assert_eq!(deserialize(DuplicateKeys::LastWins).unwrap()["a"], 3);
+    }
+
+    #[test]
+    fn merge_duplicates() {
+        let mut de = serde_json::Deserializer::from_str(JSON);
+        let map: HashableHashMap<String, u32> = MapSeed::new()
+            .merge_duplicates(|_, existing, v| *existing += v)
+            .deserialize(&mut de)
+            .unwrap();
+        assert_eq!(map["a"], 4);
+        assert_eq!(map["b"], 2);
+    }
+
+    #[test]
+    fn capacity_and_hasher() {
+        let hasher = ahash::RandomState::with_seeds(1, 2, 3, 4);
+
+        let mut de = serde_json::Deserializer::from_str("[1, 2, 3]");
+        let set: HashableHashSet<u32> = SetSeed::with_capacity_and_hasher(1000, hasher.clone())
+            .deserialize(&mut de)
+            .unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.capacity() >= 1000);
+
+        let mut de = serde_json::Deserializer::from_str(JSON);
+        let map: HashableHashMap<String, u32> = MapSeed::with_capacity_and_hasher(1000, hasher)
+            .deserialize(&mut de)
+            .unwrap();
+        assert_eq!(map.len(), 2);
+        assert!(map.capacity() >= 1000);
+    }
+
+    #[test]
+    fn deny_duplicates_field() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            #[serde(deserialize_with = "crate::de::deny_duplicates")]
+            #[allow(dead_code)]
+            settings: HashableHashMap<String, u32>,
+        }
+
+        assert!(serde_json::from_str::<Config>(r#"{"settings": {"a": 1, "b": 2}}"#).is_ok());
+        assert!(serde_json::from_str::<Config>(r#"{"settings": {"a": 1, "a": 2}}"#).is_err());
+    }
+}
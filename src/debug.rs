@@ -0,0 +1,107 @@
+//! Deterministic [`Debug`] output.
+//!
+//! While a [`SortedDebug`] is being formatted, the wrappers print their entries sorted by each
+//! entry's (or key's) `Debug` text. Because nested wrappers observe the same setting, the whole
+//! value prints identically across runs.
+
+use std::cell::Cell;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+thread_local!(static SORTED: Cell<bool> = const { Cell::new(false) });
+
+/// Returns whether sorted formatting is in effect for the current thread.
+pub(crate) fn active() -> bool {
+    SORTED.with(Cell::get)
+}
+
+/// A formatting adapter whose [`Debug`] output lists the entries of any hashable collections
+/// within `T` in sorted order. Everything else is forwarded to the wrapped value.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::{HashableHashSet, SortedDebug};
+///
+/// let set: HashableHashSet<_> = vec![3, 1, 2].into_iter().collect();
+/// assert_eq!(format!("{:?}", SortedDebug(&set)), "{1, 2, 3}");
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SortedDebug<T>(pub T);
+
+impl<T> Deref for SortedDebug<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SortedDebug<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Debug> Debug for SortedDebug<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SORTED.with(|cell| cell.set(self.0));
+            }
+        }
+
+        let _restore = Restore(SORTED.with(|cell| cell.replace(true)));
+        self.0.fmt(f)
+    }
+}
+
+/// Text that has already been rendered, written as-is so the outer formatter can indent it.
+struct Rendered(String);
+
+impl Debug for Rendered {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn render<T: Debug + ?Sized>(value: &T, f: &Formatter) -> Rendered {
+    Rendered(if f.alternate() { format!("{:#?}", value) } else { format!("{:?}", value) })
+}
+
+/// Formats set elements sorted by their `Debug` text.
+pub(crate) fn fmt_set<'a, V: Debug + 'a>(iter: impl Iterator<Item = &'a V>, f: &mut Formatter) -> fmt::Result {
+    let mut entries: Vec<Rendered> = iter.map(|v| render(v, f)).collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    f.debug_set().entries(entries).finish()
+}
+
+/// Formats map entries sorted by the `Debug` text of their keys.
+pub(crate) fn fmt_map<'a, K, V>(iter: impl Iterator<Item = (&'a K, &'a V)>, f: &mut Formatter) -> fmt::Result
+where K: Debug + 'a,
+      V: Debug + 'a,
+{
+    let mut entries: Vec<(Rendered, &V)> = iter.map(|(k, v)| (render(k, f), v)).collect();
+    entries.sort_unstable_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+    f.debug_map().entries(entries).finish()
+}
+
+#[cfg(test)]
+mod debug_test {
+    use super::SortedDebug;
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn nested_collections_are_sorted() {
+        let mut map: HashableHashMap<&str, HashableHashSet<u32>> = HashableHashMap::new();
+        for (i, key) in ["c", "a", "b"].iter().enumerate() {
+            map.insert(key, (0..3).map(|j| (i as u32 + 1) * 10 - j).collect());
+        }
+        assert_eq!(
+            format!("{:?}", SortedDebug(&map)),
+            r#"{"a": {18, 19, 20}, "b": {28, 29, 30}, "c": {10, 8, 9}}"#);
+        assert!(format!("{:#?}", SortedDebug(&map)).starts_with("{\n    \"a\": {\n        18,\n"));
+    }
+}
@@ -0,0 +1,202 @@
+//! A map with a default value for every key, built on [`HashableHashMap`].
+
+use crate::HashableHashMap;
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/// A map that behaves as if every key were present, with [`Default::default`] as the value of any
+/// key that has not been set. This models a total function with finite support.
+///
+/// Setting a key to the default value removes it, so only keys with non-default values are stored,
+/// iterated over, and considered by [`Hash`] and [`Eq`]. Two maps are therefore equal exactly when
+/// they return equal values for every key.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableDefaultHashMap;
+///
+/// let mut balances: HashableDefaultHashMap<&str, i64> = HashableDefaultHashMap::new();
+/// balances.update("alice", |b| *b += 10);
+/// balances.update("bob", |b| *b -= 5);
+/// balances.update("bob", |b| *b += 5);
+/// assert_eq!(balances["alice"], 10);
+/// assert_eq!(balances["carol"], 0);
+/// assert_eq!(balances.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct HashableDefaultHashMap<K, V, S = ahash::RandomState> {
+    // Invariant: no value equals `default`.
+    map: HashableHashMap<K, V, S>,
+    default: V,
+}
+
+impl<K, V: Default> HashableDefaultHashMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableDefaultHashMap<K, V> {
+        Default::default()
+    }
+}
+
+impl<K, V: Default, S> HashableDefaultHashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableDefaultHashMap { map: HashableHashMap::with_hasher(hasher), default: V::default() }
+    }
+}
+
+impl<K, V, S> HashableDefaultHashMap<K, V, S> {
+    /// Returns the number of keys with non-default values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if every key has the default value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over the keys with non-default values and those values, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item=(&K, &V)> {
+        self.map.iter()
+    }
+
+    /// Returns the underlying map of non-default values.
+    #[inline]
+    pub fn as_map(&self) -> &HashableHashMap<K, V, S> {
+        &self.map
+    }
+
+    /// Consumes the wrapper, returning the map of non-default values.
+    #[inline]
+    pub fn into_map(self) -> HashableHashMap<K, V, S> {
+        self.map
+    }
+}
+
+impl<K: Eq + Hash, V: Default + PartialEq, S: BuildHasher> HashableDefaultHashMap<K, V, S> {
+    /// Returns the value for `key`, which is the default value unless the key has been set.
+    pub fn get<Q>(&self, key: &Q) -> &V
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key).unwrap_or(&self.default)
+    }
+
+    /// Sets the value for `key`, returning the previous value.
+    pub fn insert(&mut self, key: K, value: V) -> V {
+        if value == self.default {
+            self.map.remove(&key).unwrap_or_default()
+        } else {
+            self.map.insert(key, value).unwrap_or_default()
+        }
+    }
+
+    /// Resets `key` to the default value, returning the previous value.
+    pub fn remove<Q>(&mut self, key: &Q) -> V
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.map.remove(key).unwrap_or_default()
+    }
+
+    /// Applies `f` to the value for `key`, starting from the default value if the key is unset.
+    pub fn update<F: FnOnce(&mut V)>(&mut self, key: K, f: F) {
+        let mut value = self.map.remove(&key).unwrap_or_default();
+        f(&mut value);
+        self.insert(key, value);
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for HashableDefaultHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K, V: Default, S: Default> Default for HashableDefaultHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableDefaultHashMap::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableDefaultHashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V: Default + PartialEq, S: BuildHasher> Extend<(K, V)> for HashableDefaultHashMap<K, V, S> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for HashableDefaultHashMap<K, V, S>
+where K: Eq + Hash,
+      V: Default + PartialEq,
+      S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut map = HashableDefaultHashMap::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for HashableDefaultHashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.map.hash(hasher);
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for HashableDefaultHashMap<K, V, S>
+where K: Eq + Hash + Borrow<Q>,
+      Q: Eq + Hash + ?Sized,
+      V: Default + PartialEq,
+      S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns the value for `key`, which is the default value unless the key has been set.
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key)
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for HashableDefaultHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+#[cfg(test)]
+mod default_map_test {
+    use crate::{hash, HashableDefaultHashMap};
+
+    #[test]
+    fn missing_keys_have_the_default() {
+        let mut map: HashableDefaultHashMap<&str, u32> = HashableDefaultHashMap::new();
+        assert_eq!(map["a"], 0);
+        assert_eq!(map.insert("a", 2), 0);
+        assert_eq!(map.insert("a", 3), 2);
+        assert_eq!(*map.get("a"), 3);
+        assert_eq!(map.remove("a"), 3);
+        assert_eq!(map.remove("a"), 0);
+    }
+
+    #[test]
+    fn default_values_are_not_stored() {
+        let a: HashableDefaultHashMap<&str, u32> = vec![("a", 1), ("b", 0)].into_iter().collect();
+        let mut b: HashableDefaultHashMap<&str, u32> = vec![("a", 1), ("c", 2)].into_iter().collect();
+        b.update("c", |v| *v -= 2);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+    }
+}
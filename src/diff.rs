@@ -0,0 +1,198 @@
+//! Structured differences between snapshots of a collection.
+//!
+//! [`HashableHashSet::diff`] and [`HashableHashMap::diff`] describe how to turn one collection
+//! into another, and `apply_diff` replays such a description. The diff types serialize as structs
+//! with named fields, so they can be sent to replicas instead of whole snapshots.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::HashableHashMap;
+//!
+//! let before = HashableHashMap::from([("a", 1), ("b", 2)]);
+//! let after = HashableHashMap::from([("b", 3), ("c", 4)]);
+//!
+//! let diff = before.diff(&after);
+//! let mut replica = before.clone();
+//! replica.apply_diff(diff);
+//! assert_eq!(replica, after);
+//! ```
+
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Serialize};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// The difference between two sets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "V: Eq + Hash + Serialize",
+    deserialize = "V: Eq + Hash + Deserialize<'de>"))]
+pub struct SetDiff<V> {
+    /// Elements present only in the newer set.
+    pub added: HashableHashSet<V>,
+    /// Elements present only in the older set.
+    pub removed: HashableHashSet<V>,
+}
+
+impl<V> SetDiff<V> {
+    /// Returns whether the two sets were equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<V: Eq + Hash> Eq for SetDiff<V> {}
+
+impl<V: Hash> Hash for SetDiff<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.added.hash(state);
+        self.removed.hash(state);
+    }
+}
+
+impl<V: Eq + Hash> PartialEq for SetDiff<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.added == other.added && self.removed == other.removed
+    }
+}
+
+/// The difference between two maps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Eq + Hash + Serialize, V: Serialize",
+    deserialize = "K: Eq + Hash + Deserialize<'de>, V: Deserialize<'de>"))]
+pub struct MapDiff<K, V> {
+    /// Entries whose keys are present only in the newer map.
+    pub added: HashableHashMap<K, V>,
+    /// Entries whose values differ, with their values in the newer map.
+    pub changed: HashableHashMap<K, V>,
+    /// Keys present only in the older map.
+    pub removed: HashableHashSet<K>,
+}
+
+impl<K, V> MapDiff<K, V> {
+    /// Returns whether the two maps were equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, V: Eq> Eq for MapDiff<K, V> {}
+
+impl<K: Hash, V: Hash> Hash for MapDiff<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.added.hash(state);
+        self.changed.hash(state);
+        self.removed.hash(state);
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq> PartialEq for MapDiff<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.added == other.added && self.changed == other.changed && self.removed == other.removed
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher> HashableHashSet<V, S> {
+    /// Returns the changes that turn this set into `newer`.
+    pub fn diff<S2: BuildHasher>(&self, newer: &HashableHashSet<V, S2>) -> SetDiff<V> {
+        SetDiff {
+            added: newer.iter().filter(|v| !self.contains(*v)).cloned().collect(),
+            removed: self.iter().filter(|v| !newer.contains(*v)).cloned().collect(),
+        }
+    }
+
+    /// Applies changes produced by [`diff`](Self::diff).
+    pub fn apply_diff(&mut self, diff: SetDiff<V>) {
+        for v in &diff.removed {
+            self.remove(v);
+        }
+        self.extend(diff.added);
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S>
+where K: Eq + Hash + Clone,
+      V: PartialEq + Clone,
+      S: BuildHasher,
+{
+    /// Returns the changes that turn this map into `newer`.
+    pub fn diff<S2: BuildHasher>(&self, newer: &HashableHashMap<K, V, S2>) -> MapDiff<K, V> {
+        let mut diff = MapDiff {
+            added: HashableHashMap::new(),
+            changed: HashableHashMap::new(),
+            removed: self.keys().filter(|k| !newer.contains_key(*k)).cloned().collect(),
+        };
+        for (k, v) in newer.iter() {
+            match self.get(k) {
+                None => {
+                    diff.added.insert(k.clone(), v.clone());
+                }
+                Some(old) if old != v => {
+                    diff.changed.insert(k.clone(), v.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        diff
+    }
+
+    /// Applies changes produced by [`diff`](Self::diff).
+    pub fn apply_diff(&mut self, diff: MapDiff<K, V>) {
+        for k in &diff.removed {
+            self.remove(k);
+        }
+        self.extend(diff.added);
+        self.extend(diff.changed);
+    }
+}
+
+#[cfg(test)]
+mod diff_test {
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn set_diff_round_trip() {
+        let before = HashableHashSet::from([1, 2, 3]);
+        let after = HashableHashSet::from([2, 3, 4]);
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, HashableHashSet::from([4]));
+        assert_eq!(diff.removed, HashableHashSet::from([1]));
+
+        let mut replica = before.clone();
+        replica.apply_diff(diff);
+        assert_eq!(replica, after);
+        assert!(after.diff(&replica).is_empty());
+    }
+
+    #[test]
+    fn map_diff_round_trip() {
+        let before = HashableHashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let after = HashableHashMap::from([("b", 2), ("c", 30), ("d", 4)]);
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, HashableHashMap::from([("d", 4)]));
+        assert_eq!(diff.changed, HashableHashMap::from([("c", 30)]));
+        assert_eq!(diff.removed, HashableHashSet::from(["a"]));
+
+        let mut replica = before.clone();
+        replica.apply_diff(diff);
+        assert_eq!(replica, after);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let before: HashableHashMap<String, u32> = HashableHashMap::from([("a".to_string(), 1)]);
+        let after = HashableHashMap::from([("b".to_string(), 2)]);
+        let diff = before.diff(&after);
+
+        let json = serde_json::to_string(&diff).unwrap();
+        assert_eq!(json, r#"{"added":{"b":2},"changed":{},"removed":["a"]}"#);
+        let mut replica = before.clone();
+        replica.apply_diff(serde_json::from_str(&json).unwrap());
+        assert_eq!(replica, after);
+
+        let set_diff = HashableHashSet::from([1]).diff(&HashableHashSet::from([2]));
+        let json = serde_json::to_string(&set_diff).unwrap();
+        assert_eq!(serde_json::from_str::<super::SetDiff<u32>>(&json).unwrap(), set_diff);
+    }
+}
@@ -0,0 +1,103 @@
+//! Type-erased values that can be hashed and compared, for heterogeneous sets.
+
+use crate::HashableHashSet;
+use std::any::{Any, TypeId};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// An object-safe counterpart to [`Hash`] and [`Eq`], implemented for every `'static` type that
+/// implements them (and [`Debug`]).
+///
+/// Values of different types are never equal, and their hashes include their [`TypeId`], which is
+/// only stable within a single build.
+pub trait DynHashable: Any + Debug {
+    /// Feeds the value, tagged with its type, into `hasher`.
+    fn dyn_hash(&self, hasher: &mut dyn Hasher);
+
+    /// Returns `true` if `other` has the same type and an equal value.
+    fn dyn_eq(&self, other: &dyn DynHashable) -> bool;
+
+    /// Returns the value as [`Any`], for downcasting.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + Debug + Eq + Hash> DynHashable for T {
+    fn dyn_hash(&self, mut hasher: &mut dyn Hasher) {
+        TypeId::of::<T>().hash(&mut hasher);
+        self.hash(&mut hasher);
+    }
+
+    fn dyn_eq(&self, other: &dyn DynHashable) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl dyn DynHashable {
+    /// Returns the value if it has type `T`.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+}
+
+impl Eq for dyn DynHashable {}
+
+impl Hash for dyn DynHashable {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.dyn_hash(hasher);
+    }
+}
+
+impl PartialEq for dyn DynHashable {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other)
+    }
+}
+
+/// A set of type-erased values, such as those provided by plugins, with the same order-independent
+/// [`Hash`] as any other [`HashableHashSet`].
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::{DynHashable, DynHashableSet};
+///
+/// let mut set: DynHashableSet = DynHashableSet::default();
+/// set.insert(Box::new(1u32) as Box<dyn DynHashable>);
+/// set.insert(Box::new("one"));
+/// set.insert(Box::new(1u64));
+/// assert!(!set.insert(Box::new(1u32)));
+/// assert_eq!(set.len(), 3);
+/// ```
+pub type DynHashableSet<S = ahash::RandomState> = HashableHashSet<Box<dyn DynHashable>, S>;
+
+#[cfg(test)]
+mod dyn_hashable_test {
+    use crate::{hash, DynHashable, DynHashableSet};
+
+    #[test]
+    fn hash_is_order_independent() {
+        let a: DynHashableSet = vec![Box::new(1u8) as Box<dyn DynHashable>, Box::new(String::from("x"))]
+            .into_iter().collect();
+        let b: DynHashableSet = vec![Box::new(String::from("x")) as Box<dyn DynHashable>, Box::new(1u8)]
+            .into_iter().collect();
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+    }
+
+    #[test]
+    fn types_are_distinguished() {
+        let a: Box<dyn DynHashable> = Box::new(1u32);
+        assert!(*a != *(Box::new(1i32) as Box<dyn DynHashable>));
+        assert_eq!(a.downcast_ref::<u32>(), Some(&1));
+        assert_eq!(a.downcast_ref::<i32>(), None);
+        assert_eq!(format!("{:?}", a), "1");
+    }
+}
@@ -0,0 +1,154 @@
+//! A hashable wrapper around [`fixedbitset`]'s dense bit set.
+//!
+//! [`fixedbitset::FixedBitSet`]'s own [`Hash`] and [`Eq`] take its length into account, so two
+//! bit sets holding the same indices can differ. [`HashableBitSet`] considers only the indices that
+//! are set, and hashes like a [`crate::HashableHashSet<usize>`] holding them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::fixedbitset::HashableBitSet;
+//! use hashable::HashableHashSet;
+//!
+//! let mut bits = HashableBitSet::with_capacity(128);
+//! bits.insert(3);
+//! let set: HashableHashSet<usize> = (&bits).into();
+//! assert_eq!(HashableBitSet::from(&set), bits);
+//! ```
+
+use crate::{hash_prehashes, prehash, HashableHashSet};
+use ::fixedbitset::FixedBitSet;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`FixedBitSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet<usize>`],
+/// and whose equality ignores the bit set's length.
+#[derive(Clone, Default)]
+#[repr(transparent)]
+pub struct HashableBitSet(FixedBitSet);
+
+impl HashableBitSet {
+    #[inline]
+    pub fn new() -> HashableBitSet {
+        Default::default()
+    }
+
+    /// Creates an empty bit set with room for indices below `bits`.
+    #[inline]
+    pub fn with_capacity(bits: usize) -> HashableBitSet {
+        HashableBitSet(FixedBitSet::with_capacity(bits))
+    }
+
+    /// Inserts `index`, growing the bit set if needed.
+    #[inline]
+    pub fn insert(&mut self, index: usize) {
+        self.0.grow(index + 1);
+        self.0.insert(index);
+    }
+
+    /// Consumes the wrapper, returning the inner [`FixedBitSet`].
+    #[inline]
+    pub fn into_inner(self) -> FixedBitSet {
+        self.0
+    }
+}
+
+impl Debug for HashableBitSet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.0.ones()).finish()
+    }
+}
+
+impl Deref for HashableBitSet {
+    type Target = FixedBitSet;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HashableBitSet {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Eq for HashableBitSet {}
+
+impl Extend<usize> for HashableBitSet {
+    #[inline]
+    fn extend<T: IntoIterator<Item=usize>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl From<FixedBitSet> for HashableBitSet {
+    #[inline]
+    fn from(bits: FixedBitSet) -> Self {
+        HashableBitSet(bits)
+    }
+}
+
+impl<S> From<&HashableHashSet<usize, S>> for HashableBitSet {
+    fn from(set: &HashableHashSet<usize, S>) -> Self {
+        let mut bits = HashableBitSet::with_capacity(set.iter().max().map_or(0, |&max| max + 1));
+        bits.extend(set.iter().copied());
+        bits
+    }
+}
+
+impl<S: BuildHasher + Default> From<&HashableBitSet> for HashableHashSet<usize, S> {
+    fn from(bits: &HashableBitSet) -> Self {
+        let mut set = HashableHashSet::with_capacity_and_hasher(bits.0.count_ones(..), S::default());
+        set.extend(bits.0.ones());
+        set
+    }
+}
+
+impl FromIterator<usize> for HashableBitSet {
+    fn from_iter<T: IntoIterator<Item=usize>>(iter: T) -> Self {
+        HashableBitSet(FixedBitSet::from_iter(iter))
+    }
+}
+
+impl Hash for HashableBitSet {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.ones().map(|i| prehash(&i)), hasher);
+    }
+}
+
+impl PartialEq for HashableBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        // `ones` yields indices in ascending order.
+        self.0.ones().eq(other.0.ones())
+    }
+}
+
+#[cfg(test)]
+mod fixedbitset_test {
+    use super::HashableBitSet;
+    use crate::{hash, HashableHashSet};
+
+    #[test]
+    fn equality_ignores_length() {
+        let mut short = HashableBitSet::with_capacity(8);
+        short.insert(5);
+        let mut long = HashableBitSet::with_capacity(1024);
+        long.insert(5);
+        assert_eq!(short, long);
+        assert_eq!(hash(&short), hash(&long));
+        long.insert(700);
+        assert_ne!(short, long);
+    }
+
+    #[test]
+    fn matches_hash_set() {
+        let set = HashableHashSet::from([1usize, 64, 65]);
+        let bits = HashableBitSet::from(&set);
+        assert_eq!(hash(&bits), hash(&set));
+        assert_eq!(HashableHashSet::<usize>::from(&bits), set);
+        assert_eq!(bits, vec![65, 1, 64].into_iter().collect());
+    }
+}
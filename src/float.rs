@@ -0,0 +1,145 @@
+//! Floating-point adapters with total equality, ordering, and hashing.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+macro_rules! hashable_float {
+    ($(#[$attr:meta])* $name:ident, $float:ty, $bits:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+        #[serde(transparent)]
+        #[repr(transparent)]
+        pub struct $name(pub $float);
+
+        impl $name {
+            /// Returns the wrapped value.
+            #[inline]
+            pub fn get(self) -> $float {
+                self.0
+            }
+
+            /// Returns the bit pattern used for equality and hashing: every NaN maps to the
+            /// canonical NaN, and `-0.0` maps to `0.0`.
+            #[inline]
+            pub fn canonical_bits(self) -> $bits {
+                if self.0.is_nan() {
+                    <$float>::NAN.to_bits()
+                } else if self.0 == 0.0 {
+                    0
+                } else {
+                    self.0.to_bits()
+                }
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                Debug::fmt(&self.0, f) // transparent
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl From<$float> for $name {
+            #[inline]
+            fn from(value: $float) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $float {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Hash for $name {
+            #[inline]
+            fn hash<H: Hasher>(&self, hasher: &mut H) {
+                self.canonical_bits().hash(hasher);
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                match (self.0.is_nan(), other.0.is_nan()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => self.0.partial_cmp(&other.0).unwrap(),
+                }
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.canonical_bits() == other.canonical_bits()
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+    };
+}
+
+hashable_float!(
+    /// An [`f64`] with total [`Eq`], [`Ord`], and [`Hash`] implementations, so floating-point
+    /// values can be stored in [`crate::HashableHashSet`]s and used as map keys.
+    ///
+    /// All NaNs are equal to each other and greater than every other value, and `-0.0` equals
+    /// `0.0`. Otherwise values compare as usual. The wrapped value is left as is, so a NaN payload
+    /// or the sign of a zero survives a round trip.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hashable::{HashableF64, HashableHashSet};
+    ///
+    /// let set: HashableHashSet<HashableF64> =
+    ///     vec![0.0, -0.0, f64::NAN, -f64::NAN, 1.5].into_iter().map(HashableF64).collect();
+    /// assert_eq!(set.len(), 3);
+    /// assert!(set.contains(&HashableF64(f64::NAN)));
+    /// ```
+    HashableF64, f64, u64
+);
+
+hashable_float!(
+    /// An [`f32`] with total [`Eq`], [`Ord`], and [`Hash`] implementations. See [`HashableF64`]
+    /// for how NaNs and zeros are treated.
+    HashableF32, f32, u32
+);
+
+#[cfg(test)]
+mod float_test {
+    use crate::{hash, HashableF32, HashableF64};
+
+    #[test]
+    fn nan_and_zero_policy() {
+        assert_eq!(HashableF64(f64::NAN), HashableF64(-f64::NAN));
+        assert_eq!(hash(&HashableF64(f64::NAN)), hash(&HashableF64(f64::from_bits(f64::NAN.to_bits() | 1))));
+        assert_eq!(HashableF64(0.0), HashableF64(-0.0));
+        assert_eq!(hash(&HashableF32(0.0)), hash(&HashableF32(-0.0)));
+        assert_ne!(HashableF64(1.0), HashableF64(-1.0));
+    }
+
+    #[test]
+    fn total_order() {
+        let mut values: Vec<HashableF64> =
+            vec![f64::NAN, 1.0, f64::NEG_INFINITY, -0.0, f64::INFINITY].into_iter().map(HashableF64).collect();
+        values.sort();
+        assert_eq!(values, [f64::NEG_INFINITY, 0.0, 1.0, f64::INFINITY, f64::NAN].map(HashableF64));
+    }
+}
@@ -0,0 +1,235 @@
+//! Immutable collections that compute their hash once.
+
+use crate::{prehash, HashableHashMap, HashableHashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Deref;
+
+/// An immutable [`HashableHashSet`] that stores its hash, created by [`HashableHashSet::freeze`].
+///
+/// [`Hash`] writes the stored value in constant time, and [`Eq`] compares the stored values before
+/// falling back to comparing elements. The hash therefore differs from that of the set itself, so
+/// frozen and unfrozen sets should not be mixed as keys in the same table. Read access is available
+/// through [`Deref`].
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableHashSet;
+///
+/// let frozen = HashableHashSet::from([1, 2, 3]).freeze();
+/// assert!(frozen.contains(&2));
+/// assert_eq!(frozen, HashableHashSet::from([3, 2, 1]).freeze());
+/// ```
+#[derive(Clone)]
+pub struct FrozenHashableHashSet<V, S = ahash::RandomState> {
+    set: HashableHashSet<V, S>,
+    hash: u64,
+}
+
+impl<V: Hash, S> HashableHashSet<V, S> {
+    /// Makes the set immutable and computes its hash once.
+    pub fn freeze(self) -> FrozenHashableHashSet<V, S> {
+        FrozenHashableHashSet { hash: prehash(&self), set: self }
+    }
+}
+
+impl<V, S> FrozenHashableHashSet<V, S> {
+    /// Returns the hash computed by [`HashableHashSet::freeze`].
+    #[inline]
+    pub fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the set so it can be modified again.
+    #[inline]
+    pub fn thaw(self) -> HashableHashSet<V, S> {
+        self.set
+    }
+}
+
+impl<V: Debug, S> Debug for FrozenHashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.set.fmt(f) // transparent
+    }
+}
+
+impl<V, S> Deref for FrozenHashableHashSet<V, S> {
+    type Target = HashableHashSet<V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl<'de, V, S> serde::Deserialize<'de> for FrozenHashableHashSet<V, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashSet::deserialize(de).map(HashableHashSet::freeze)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Eq for FrozenHashableHashSet<V, S> {}
+
+impl<V: Hash, S> From<HashableHashSet<V, S>> for FrozenHashableHashSet<V, S> {
+    #[inline]
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.freeze()
+    }
+}
+
+impl<V, S> Hash for FrozenHashableHashSet<V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.hash);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a FrozenHashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = std::collections::hash_set::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_set::Iter<'a, V> {
+        self.set.iter()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialEq for FrozenHashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.set == other.set
+    }
+}
+
+impl<V: Eq + Hash + serde::Serialize, S: BuildHasher> serde::Serialize for FrozenHashableHashSet<V, S> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.set.serialize(ser)
+    }
+}
+
+/// An immutable [`HashableHashMap`] that stores its hash, created by [`HashableHashMap::freeze`].
+///
+/// See [`FrozenHashableHashSet`] for how [`Hash`] and [`Eq`] behave.
+#[derive(Clone)]
+pub struct FrozenHashableHashMap<K, V, S = ahash::RandomState> {
+    map: HashableHashMap<K, V, S>,
+    hash: u64,
+}
+
+impl<K: Hash, V: Hash, S> HashableHashMap<K, V, S> {
+    /// Makes the map immutable and computes its hash once.
+    pub fn freeze(self) -> FrozenHashableHashMap<K, V, S> {
+        FrozenHashableHashMap { hash: prehash(&self), map: self }
+    }
+}
+
+impl<K, V, S> FrozenHashableHashMap<K, V, S> {
+    /// Returns the hash computed by [`HashableHashMap::freeze`].
+    #[inline]
+    pub fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the map so it can be modified again.
+    #[inline]
+    pub fn thaw(self) -> HashableHashMap<K, V, S> {
+        self.map
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for FrozenHashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.map.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S> Deref for FrozenHashableHashMap<K, V, S> {
+    type Target = HashableHashMap<K, V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<'de, K, V, S> serde::Deserialize<'de> for FrozenHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Deserialize<'de>,
+      V: Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashMap::deserialize(de).map(HashableHashMap::freeze)
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for FrozenHashableHashMap<K, V, S> {}
+
+impl<K: Hash, V: Hash, S> From<HashableHashMap<K, V, S>> for FrozenHashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        map.freeze()
+    }
+}
+
+impl<K, V, S> Hash for FrozenHashableHashMap<K, V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.hash);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a FrozenHashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::collections::hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::Iter<'a, K, V> {
+        self.map.iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for FrozenHashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.map == other.map
+    }
+}
+
+impl<K, V, S> serde::Serialize for FrozenHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Serialize,
+      V: serde::Serialize,
+      S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.map.serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod frozen_test {
+    use crate::{hash, HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn frozen_equality_and_hash() {
+        let a = HashableHashSet::from([1, 2, 3]).freeze();
+        let b = HashableHashSet::from([3, 1, 2]).freeze();
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(a, HashableHashSet::from([1, 2]).freeze());
+
+        let map = HashableHashMap::from([("a", 1)]).freeze();
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.clone().thaw(), HashableHashMap::from([("a", 1)]));
+        assert_eq!(hash(&map), hash(&HashableHashMap::from([("a", 1)]).freeze()));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let frozen = HashableHashSet::from([1, 2]).freeze();
+        let json = serde_json::to_string(&frozen).unwrap();
+        let parsed: crate::FrozenHashableHashSet<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.precomputed_hash(), frozen.precomputed_hash());
+    }
+}
@@ -0,0 +1,378 @@
+//! A memory-mapped on-disk format for large read-only sets.
+
+use crate::stable::{stable_prehash, StableHasher};
+use memmap2::Mmap;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"HSHFSET2";
+const HEADER_LEN: usize = 40;
+/// Identifies the hash function in the header. Only [`StableHasher`] is defined so far.
+const HASHER_FNV1A_SPLITMIX: u64 = 1;
+
+/// An error from [`FrozenSetFile::open`].
+#[derive(Debug)]
+pub enum FrozenSetFileError {
+    /// The file could not be opened or mapped.
+    Io(io::Error),
+    /// The file is not a frozen set file, or it is truncated.
+    Format(&'static str),
+}
+
+impl Display for FrozenSetFileError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FrozenSetFileError::Io(e) => write!(f, "failed to map frozen set file: {}", e),
+            FrozenSetFileError::Format(reason) => write!(f, "invalid frozen set file: {}", reason),
+        }
+    }
+}
+
+impl Error for FrozenSetFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FrozenSetFileError::Io(e) => Some(e),
+            FrozenSetFileError::Format(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for FrozenSetFileError {
+    fn from(e: io::Error) -> Self {
+        FrozenSetFileError::Io(e)
+    }
+}
+
+/// A read-only set of byte strings backed by a memory-mapped file.
+///
+/// [`FrozenSetFile::create`] sorts the elements by pre-hash and writes them together with an
+/// index and the set's hash. [`FrozenSetFile::open`] maps the file into memory without reading or
+/// rebuilding it, so even sets with hundreds of millions of entries are available immediately and
+/// are paged in on demand.
+///
+/// The pre-hashes and the set's hash come from a fixed hash function rather than the per-process
+/// pre-hash used in memory, and the header records which function that is, so a file written by
+/// one process can be opened by any other, on any platform.
+///
+/// Like [`crate::FrozenHashableHashSet`], [`Hash`] writes the stored hash in constant time, and
+/// [`Eq`] compares the stored hashes before comparing elements.
+///
+/// # Layout
+///
+/// All integers are little-endian `u64`s:
+///
+/// - a header: the magic bytes `HSHFSET2`, the hash function (`1` for 64-bit FNV-1a followed by
+///   the SplitMix64 finalizer), the set's hash, the number of elements `n`, and the number of
+///   index bits `b`;
+/// - the index: `2^b + 1` positions, where the elements whose pre-hashes have the top `b` bits
+///   equal to `i` start at position `i`;
+/// - the pre-hashes of the `n` elements in ascending order;
+/// - `n + 1` offsets delimiting each element's bytes in the data section;
+/// - the data section, holding the elements' bytes back to back.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::FrozenSetFile;
+///
+/// let path = std::env::temp_dir().join(format!("blocklist-{}.hset", std::process::id()));
+/// FrozenSetFile::create(&path, vec!["evil.example", "spam.example"])?;
+/// let blocklist = FrozenSetFile::open(&path)?;
+/// assert!(blocklist.contains(b"evil.example"));
+/// assert!(!blocklist.contains(b"good.example"));
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct FrozenSetFile {
+    mmap: Mmap,
+    hash: u64,
+    len: usize,
+    bits: u32,
+    // Byte offsets of the sections within the mapping.
+    prehashes: usize,
+    offsets: usize,
+    data: usize,
+}
+
+impl FrozenSetFile {
+    /// Writes the distinct `elements` to `writer` in the frozen set format, returning the set's
+    /// hash.
+    pub fn write<I, T, W>(elements: I, writer: W) -> io::Result<u64>
+    where I: IntoIterator<Item=T>,
+          T: AsRef<[u8]>,
+          W: Write,
+    {
+        let mut entries: Vec<(u64, T)> = elements.into_iter().map(|e| (stable_prehash(e.as_ref()), e)).collect();
+        entries.sort_unstable_by(|(a, x), (b, y)| a.cmp(b).then_with(|| x.as_ref().cmp(y.as_ref())));
+        entries.dedup_by(|(a, x), (b, y)| a == b && x.as_ref() == y.as_ref());
+
+        // Equivalent to hashing a `HashableHashSet<Vec<u8>>` with `stable_prehash`.
+        let mut hasher = StableHasher::default();
+        for (hash, _) in &entries {
+            hasher.write_u64(*hash);
+        }
+        let hash = hasher.finish();
+
+        let len = entries.len() as u64;
+        let bits = index_bits(entries.len());
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        for word in &[HASHER_FNV1A_SPLITMIX, hash, len, u64::from(bits)] {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        let mut position = 0;
+        for bucket in 0..=(1u64 << bits) {
+            while position < entries.len() && bucket_of(entries[position].0, bits) < bucket {
+                position += 1;
+            }
+            writer.write_all(&(position as u64).to_le_bytes())?;
+        }
+        for (hash, _) in &entries {
+            writer.write_all(&hash.to_le_bytes())?;
+        }
+        let mut offset = 0u64;
+        writer.write_all(&offset.to_le_bytes())?;
+        for (_, element) in &entries {
+            offset += element.as_ref().len() as u64;
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        for (_, element) in &entries {
+            writer.write_all(element.as_ref())?;
+        }
+        writer.flush()?;
+        Ok(hash)
+    }
+
+    /// Writes the distinct `elements` to a new file at `path`, returning the set's hash.
+    pub fn create<P, I, T>(path: P, elements: I) -> io::Result<u64>
+    where P: AsRef<Path>,
+          I: IntoIterator<Item=T>,
+          T: AsRef<[u8]>,
+    {
+        FrozenSetFile::write(elements, File::create(path)?)
+    }
+
+    /// Maps the file at `path` into memory and validates its header and index. A file that passes
+    /// validation can be queried without panicking, however its bytes were produced.
+    ///
+    /// The file must not be modified while it is open: validation happens only here, so lookups
+    /// on a file that changes afterwards can panic or give wrong answers.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<FrozenSetFile, FrozenSetFileError> {
+        let file = File::open(path)?;
+        // SAFETY: The mapping is only read through bounds-checked slices, and the caller is
+        // responsible for not modifying the file while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < HEADER_LEN || &mmap[..8] != MAGIC {
+            return Err(FrozenSetFileError::Format("missing header"));
+        }
+        if read_u64(&mmap, 8) != HASHER_FNV1A_SPLITMIX {
+            return Err(FrozenSetFileError::Format("unsupported hash function"));
+        }
+        let hash = read_u64(&mmap, 16);
+        let len = read_u64(&mmap, 24);
+        let bits = read_u64(&mmap, 32);
+        if bits >= 48 || len > mmap.len() as u64 {
+            return Err(FrozenSetFileError::Format("implausible header"));
+        }
+        // The section sizes are computed in `u64` so that they cannot overflow `usize` on 32-bit
+        // targets; once they fit within the mapping, they fit in `usize` too.
+        let (prehashes, offsets, data) = match section_offsets(len, bits as u32) {
+            Some(sections) if sections.2 <= mmap.len() as u64 => sections,
+            _ => return Err(FrozenSetFileError::Format("truncated index")),
+        };
+        let (len, bits) = (len as usize, bits as u32);
+        let (prehashes, offsets, data) = (prehashes as usize, offsets as usize, data as usize);
+        if read_u64(&mmap, prehashes - 8) != len as u64 {
+            return Err(FrozenSetFileError::Format("truncated index"));
+        }
+        if (mmap.len() - data) as u64 != read_u64(&mmap, data - 8) {
+            return Err(FrozenSetFileError::Format("truncated data"));
+        }
+        // With the last entries checked above, non-decreasing bucket starts and element offsets
+        // keep every range that a lookup reads within the file.
+        if !is_non_decreasing(&mmap[HEADER_LEN..prehashes]) || !is_non_decreasing(&mmap[offsets..data]) {
+            return Err(FrozenSetFileError::Format("corrupt index"));
+        }
+        Ok(FrozenSetFile { mmap, hash, len, bits, prehashes, offsets, data })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the hash stored in the file.
+    #[inline]
+    pub fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<T: AsRef<[u8]> + ?Sized>(&self, value: &T) -> bool {
+        let value = value.as_ref();
+        let hash = stable_prehash(value);
+        let bucket = bucket_of(hash, self.bits) as usize;
+        let start = read_u64(&self.mmap, HEADER_LEN + bucket * 8) as usize;
+        let end = read_u64(&self.mmap, HEADER_LEN + (bucket + 1) * 8) as usize;
+        (start..end)
+            .skip_while(|&i| self.prehash_at(i) < hash)
+            .take_while(|&i| self.prehash_at(i) == hash)
+            .any(|i| self.element(i) == value)
+    }
+
+    /// Iterates over the elements in ascending order of pre-hash.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item=&[u8]> {
+        (0..self.len).map(move |i| self.element(i))
+    }
+
+    fn prehash_at(&self, i: usize) -> u64 {
+        read_u64(&self.mmap, self.prehashes + i * 8)
+    }
+
+    fn element(&self, i: usize) -> &[u8] {
+        let start = read_u64(&self.mmap, self.offsets + i * 8) as usize;
+        let end = read_u64(&self.mmap, self.offsets + (i + 1) * 8) as usize;
+        &self.mmap[self.data + start..self.data + end]
+    }
+}
+
+/// Returns the number of index bits for `len` elements, giving about one element per bucket.
+fn index_bits(len: usize) -> u32 {
+    len.next_power_of_two().trailing_zeros()
+}
+
+/// Returns the byte offsets of the pre-hash, offset, and data sections for `len` elements and
+/// `bits` index bits, or `None` if they overflow.
+fn section_offsets(len: u64, bits: u32) -> Option<(u64, u64, u64)> {
+    let prehashes = 1u64.checked_shl(bits)?.checked_add(1)?.checked_mul(8)?.checked_add(HEADER_LEN as u64)?;
+    let offsets = len.checked_mul(8)?.checked_add(prehashes)?;
+    let data = len.checked_add(1)?.checked_mul(8)?.checked_add(offsets)?;
+    Some((prehashes, offsets, data))
+}
+
+fn bucket_of(prehash: u64, bits: u32) -> u64 {
+    prehash.checked_shr(64 - bits).unwrap_or(0)
+}
+
+/// Returns whether the little-endian `u64`s in `bytes` never decrease.
+fn is_non_decreasing(bytes: &[u8]) -> bool {
+    let mut previous = 0;
+    bytes.chunks_exact(8).all(|chunk| {
+        let next = read_u64(chunk, 0);
+        let ordered = previous <= next;
+        previous = next;
+        ordered
+    })
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+impl Debug for FrozenSetFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // The set may be far too large to list.
+        f.debug_struct("FrozenSetFile").field("len", &self.len).field("hash", &self.hash).finish()
+    }
+}
+
+impl Eq for FrozenSetFile {}
+
+impl Hash for FrozenSetFile {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.hash);
+    }
+}
+
+impl PartialEq for FrozenSetFile {
+    fn eq(&self, other: &Self) -> bool {
+        // Equal sets store their elements in the same order.
+        self.hash == other.hash && self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod frozen_file_test {
+    use super::{FrozenSetFile, FrozenSetFileError};
+    use crate::HashableHashSet;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("hashable-{}-{}.hset", name, std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round-trip");
+        let elements: Vec<String> = (0..1000).map(|i| format!("element-{}", i)).collect();
+        let hash = FrozenSetFile::create(&path, elements.iter().chain(&elements[..10])).unwrap();
+        let set = FrozenSetFile::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(set.len(), 1000);
+        assert!(elements.iter().all(|e| set.contains(e)));
+        assert!(!set.contains("element-1000"));
+        let std: HashableHashSet<Vec<u8>> = elements.iter().map(|e| e.clone().into_bytes()).collect();
+        assert_eq!(hash, crate::stable::stable_prehash(&std));
+        assert_eq!(set.precomputed_hash(), hash);
+    }
+
+    #[test]
+    fn empty_and_invalid_files() {
+        let path = temp_path("empty");
+        FrozenSetFile::create(&path, Vec::<&[u8]>::new()).unwrap();
+        let set = FrozenSetFile::open(&path).unwrap();
+        assert!(set.is_empty() && !set.contains(""));
+
+        std::fs::write(&path, b"not a frozen set file").unwrap();
+        assert!(matches!(FrozenSetFile::open(&path), Err(FrozenSetFileError::Format(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn format_is_fixed() {
+        let path = temp_path("fixed");
+        let hash = FrozenSetFile::create(&path, ["a", "bb"]).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let set = FrozenSetFile::open(&path).unwrap();
+
+        // Files must be readable by other processes, so neither may depend on the process.
+        assert_eq!(hash, 4052399033423315003);
+        assert_eq!(&bytes[..16], b"HSHFSET2\x01\0\0\0\0\0\0\0");
+        assert!(set.contains("a") && set.contains("bb"));
+
+        let mut other_hasher = bytes;
+        other_hasher[8] = 2;
+        std::fs::write(&path, &other_hasher).unwrap();
+        assert!(matches!(FrozenSetFile::open(&path), Err(FrozenSetFileError::Format(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupt_index() {
+        let path = temp_path("corrupt-index");
+        FrozenSetFile::create(&path, ["a", "bb", "ccc"]).unwrap();
+        let valid = std::fs::read(&path).unwrap();
+        let offsets = valid.len() - 6 - 4 * 8;
+
+        // The second bucket start and the second element offset, each made to exceed the next.
+        for at in [40 + 8, offsets + 8] {
+            let mut corrupt = valid.clone();
+            corrupt[at..at + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+            std::fs::write(&path, &corrupt).unwrap();
+            assert!(matches!(FrozenSetFile::open(&path), Err(FrozenSetFileError::Format(_))));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,301 @@
+//! Types returned by [`HashableHashMap`] methods.
+//!
+//! The entry types wrap their std counterparts rather than re-exporting them, so every operation
+//! on an entry, such as inserting, replacing, or removing a value, passes through this crate. This
+//! is where bookkeeping like a cached hash would observe changes made through the entry API.
+
+use crate::HashableHashMap;
+use std::borrow::Borrow;
+use std::collections::hash_map;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+/// A view into a single entry of a [`HashableHashMap`], which may be vacant or occupied. Returned
+/// by [`HashableHashMap::entry`].
+pub enum Entry<'a, K, V> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference to
+    /// the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.or_insert_with_key(|_| default())
+    }
+
+    /// Inserts the result of `default`, which is passed the key, if the entry is vacant, and
+    /// returns a mutable reference to the value.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Calls `f` with the value if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    /// Inserts the default value if the entry is vacant, and returns a mutable reference to the
+    /// value.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for Entry<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+/// An occupied entry. Part of [`Entry`].
+pub struct OccupiedEntry<'a, K, V>(hash_map::OccupiedEntry<'a, K, V>);
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Returns the entry's key.
+    pub fn key(&self) -> &K {
+        self.0.key()
+    }
+
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &V {
+        self.0.get()
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.0.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference to the value that outlives the entry.
+    pub fn into_mut(self) -> &'a mut V {
+        self.0.into_mut()
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        self.0.insert(value)
+    }
+
+    /// Removes the entry, returning its value.
+    pub fn remove(self) -> V {
+        self.0.remove()
+    }
+
+    /// Removes the entry, returning its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        self.0.remove_entry()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for OccupiedEntry<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A vacant entry. Part of [`Entry`].
+pub struct VacantEntry<'a, K, V>(hash_map::VacantEntry<'a, K, V>);
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Returns the key that would be used when inserting.
+    pub fn key(&self) -> &K {
+        self.0.key()
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.0.into_key()
+    }
+
+    /// Inserts `value`, returning a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.0.insert(value)
+    }
+}
+
+impl<K: Debug, V> Debug for VacantEntry<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+/// The error returned by [`HashableHashMap::try_insert`] when the key is already present. It holds
+/// the entry for that key and the value that was not inserted.
+pub struct OccupiedError<'a, K, V> {
+    /// The entry for the key that is already present.
+    pub entry: OccupiedEntry<'a, K, V>,
+    /// The value that was not inserted.
+    pub value: V,
+}
+
+impl<K: Debug, V: Debug> Debug for OccupiedError<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("key", self.entry.key())
+            .field("old_value", self.entry.get())
+            .field("new_value", &self.value)
+            .finish()
+    }
+}
+
+impl<K: Debug, V: Debug> Display for OccupiedError<'_, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f, "failed to insert {:?}, key {:?} already exists with value {:?}",
+            self.value, self.entry.key(), self.entry.get())
+    }
+}
+
+impl<K: Debug, V: Debug> Error for OccupiedError<'_, K, V> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashableHashMap<K, V, S> {
+    /// Returns the entry for `key` for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.0.entry(key) {
+            hash_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry(entry)),
+            hash_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry(entry)),
+        }
+    }
+
+    /// Inserts a key-value pair unless the key is already present, in which case nothing is
+    /// updated and an error holding the existing entry and the new value is returned.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<&mut V, OccupiedError<'_, K, V>> {
+        match self.entry(key) {
+            Entry::Occupied(entry) => Err(OccupiedError { entry, value }),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
+    }
+
+    /// Applies `f` to the value for `key`, returning whether the key was present.
+    pub fn update<Q, F>(&mut self, key: &Q, f: F) -> bool
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+          F: FnOnce(&mut V),
+    {
+        match self.0.get_mut(key) {
+            Some(value) => {
+                f(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies `update` to the value for `key` if present, and otherwise inserts the result of
+    /// `insert`. Returns a mutable reference to the value.
+    pub fn upsert<I, U>(&mut self, key: K, insert: I, update: U) -> &mut V
+    where I: FnOnce() -> V,
+          U: FnOnce(&mut V),
+    {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => {
+                update(entry.get_mut());
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => entry.insert(insert()),
+        }
+    }
+
+    /// Moves the entries of `other` into this map. When a key is already present, `resolve` is
+    /// called with the key, the existing value, and the incoming value, and decides the result by
+    /// updating the existing value.
+    pub fn merge_from<I, F>(&mut self, other: I, mut resolve: F)
+    where I: IntoIterator<Item=(K, V)>,
+          F: FnMut(&K, &mut V, V),
+    {
+        for (k, v) in other {
+            match self.0.get_mut(&k) {
+                Some(mine) => resolve(&k, mine, v),
+                None => {
+                    self.0.insert(k, v);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hash_map_test {
+    use super::Entry;
+    use crate::HashableHashMap;
+
+    #[test]
+    fn entry() {
+        let mut map: HashableHashMap<&str, Vec<u32>> = HashableHashMap::new();
+        map.entry("a").or_default().push(1);
+        map.entry("a").and_modify(|v| v.push(2)).or_insert(vec![0]);
+        map.entry("b").or_insert_with_key(|k| vec![k.len() as u32]);
+        assert_eq!(map["a"], [1, 2]);
+        assert_eq!(map["b"], [1]);
+
+        match map.entry("a") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), ("a", vec![1, 2])),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert!(matches!(map.entry("a"), Entry::Vacant(_)));
+    }
+
+    #[test]
+    fn update_and_upsert() {
+        let mut map: HashableHashMap<String, Vec<u32>> = HashableHashMap::new();
+        assert!(!map.update("a", |v| v.push(0)));
+        map.upsert("a".to_string(), || vec![1], |v| v.push(2));
+        map.upsert("a".to_string(), || vec![1], |v| v.push(2));
+        assert!(map.update("a", |v| v.push(3)));
+        assert_eq!(map["a"], [1, 2, 3]);
+    }
+
+    #[test]
+    fn merge_from() {
+        let mut totals = HashableHashMap::from([("a", 1), ("b", 2)]);
+        let partial = HashableHashMap::from([("b", 3), ("c", 4)]);
+        totals.merge_from(partial, |_, mine, theirs| *mine += theirs);
+        assert_eq!(totals, HashableHashMap::from([("a", 1), ("b", 5), ("c", 4)]));
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut map = HashableHashMap::new();
+        *map.try_insert("a", 1).unwrap() += 1;
+
+        let error = map.try_insert("a", 3).unwrap_err();
+        assert_eq!(error.value, 3);
+        assert_eq!(error.entry.get(), &2);
+        assert_eq!(error.to_string(), r#"failed to insert 3, key "a" already exists with value 2"#);
+        assert_eq!(map["a"], 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Types returned by [`HashableHashSet`] methods.
+
+use crate::HashableHashSet;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+/// A lazy iterator over every subset of a set, starting with the empty set. Returned by
+/// [`HashableHashSet::subsets`].
+pub struct Subsets<'a, V, S> {
+    elements: Vec<&'a V>,
+    hasher: S,
+    // The next subset as a binary counter over `elements`, or `None` once exhausted.
+    included: Option<Vec<bool>>,
+}
+
+impl<'a, V, S> Iterator for Subsets<'a, V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Clone,
+{
+    type Item = HashableHashSet<V, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let elements = &self.elements;
+        let included = self.included.as_mut()?;
+        let mut subset = HashableHashSet::with_hasher(self.hasher.clone());
+        subset.extend(elements.iter().zip(included.iter()).filter(|(_, &i)| i).map(|(v, _)| (*v).clone()));
+
+        // Increment the counter, finishing when it wraps around.
+        match included.iter().position(|&i| !i) {
+            Some(first_zero) => {
+                included[..first_zero].iter_mut().for_each(|i| *i = false);
+                included[first_zero] = true;
+            }
+            None => self.included = None,
+        }
+        Some(subset)
+    }
+}
+
+impl<V: Debug, S> Debug for Subsets<'_, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Subsets").field("elements", &self.elements).finish()
+    }
+}
+
+/// A lazy iterator over the subsets of a set with a given size. Returned by
+/// [`HashableHashSet::subsets_of_size`].
+pub struct SubsetsOfSize<'a, V, S> {
+    elements: Vec<&'a V>,
+    hasher: S,
+    // Strictly increasing indices into `elements` of the next subset, or `None` once exhausted.
+    indices: Option<Vec<usize>>,
+}
+
+impl<'a, V, S> Iterator for SubsetsOfSize<'a, V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Clone,
+{
+    type Item = HashableHashSet<V, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let elements = &self.elements;
+        let indices = self.indices.as_mut()?;
+        let mut subset = HashableHashSet::with_capacity_and_hasher(indices.len(), self.hasher.clone());
+        subset.extend(indices.iter().map(|&i| elements[i].clone()));
+
+        // Advance to the next combination in lexicographic order.
+        let n = elements.len();
+        let k = indices.len();
+        match (0..k).rev().find(|&i| indices[i] != i + n - k) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+            None => self.indices = None,
+        }
+        Some(subset)
+    }
+}
+
+impl<V: Debug, S> Debug for SubsetsOfSize<'_, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SubsetsOfSize")
+            .field("elements", &self.elements)
+            .field("size", &self.indices.as_ref().map(Vec::len))
+            .finish()
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> HashableHashSet<V, S> {
+    /// Returns a lazy iterator over all `2^n` subsets of the set.
+    pub fn subsets(&self) -> Subsets<'_, V, S> {
+        Subsets {
+            elements: self.iter().collect(),
+            hasher: self.hasher().clone(),
+            included: Some(vec![false; self.len()]),
+        }
+    }
+
+    /// Returns a lazy iterator over the subsets of the set with exactly `size` elements.
+    pub fn subsets_of_size(&self, size: usize) -> SubsetsOfSize<'_, V, S> {
+        SubsetsOfSize {
+            elements: self.iter().collect(),
+            hasher: self.hasher().clone(),
+            indices: if size <= self.len() { Some((0..size).collect()) } else { None },
+        }
+    }
+}
+
+#[cfg(test)]
+mod hash_set_test {
+    use crate::HashableHashSet;
+
+    #[test]
+    fn subsets() {
+        let set = HashableHashSet::from([1, 2, 3]);
+        let subsets: HashableHashSet<HashableHashSet<u32>> = set.subsets().collect();
+        assert_eq!(subsets.len(), 8);
+        assert!(subsets.contains(&HashableHashSet::new()));
+        assert!(subsets.contains(&set));
+
+        assert_eq!(HashableHashSet::<u32>::new().subsets().count(), 1);
+    }
+
+    #[test]
+    fn subsets_of_size() {
+        let set = HashableHashSet::from([1, 2, 3, 4]);
+        let pairs: HashableHashSet<HashableHashSet<u32>> = set.subsets_of_size(2).collect();
+        assert_eq!(pairs.len(), 6);
+        assert!(pairs.iter().all(|pair| pair.len() == 2));
+
+        assert_eq!(set.subsets_of_size(0).count(), 1);
+        assert_eq!(set.subsets_of_size(4).count(), 1);
+        assert_eq!(set.subsets_of_size(5).count(), 0);
+    }
+}
@@ -0,0 +1,371 @@
+//! Hashable wrappers around [`hashbrown`]'s collections, which expose APIs that std does not
+//! provide, such as the raw entry API for lookups and insertions with precomputed hashes.
+//!
+//! Code that already stores [`hashbrown`] collections can hash them in place by viewing them
+//! through `from_ref`, without converting or copying. The wrappers hash identically to the std
+//! ones.
+//!
+//! This module does not make the crate usable without `std`: the wrappers are for code that already
+//! uses [`hashbrown`] on a `std` target, and `no_std` support is out of scope.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::hashbrown::HashableHashMap;
+//! use std::hash::BuildHasher;
+//!
+//! let mut map: HashableHashMap<String, u32> = HashableHashMap::new();
+//! let hash = map.hasher().hash_one("key");
+//! map.raw_entry_mut()
+//!     .from_key_hashed_nocheck(hash, "key")
+//!     .or_insert_with(|| ("key".to_string(), 1));
+//! assert_eq!(map.raw_entry().from_key_hashed_nocheck(hash, "key"), Some((&"key".to_string(), &1)));
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::hashbrown::hash_map::{RawEntryBuilder, RawEntryBuilderMut};
+use ::hashbrown::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`hashbrown::HashSet`] wrapper that implements [`Hash`] like
+/// [`crate::HashableHashSet`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableHashSet<V, S = ahash::RandomState>(HashSet<V, S>);
+
+impl<V> HashableHashSet<V> {
+    #[inline]
+    pub fn new() -> HashableHashSet<V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableHashSet<V> {
+        HashableHashSet::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<V, S> HashableHashSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableHashSet(HashSet::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableHashSet(HashSet::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Views an existing [`hashbrown::HashSet`] as a `HashableHashSet` without copying it.
+    #[inline]
+    pub fn from_ref(set: &HashSet<V, S>) -> &Self {
+        // SAFETY: `HashableHashSet` is a transparent wrapper.
+        unsafe { &*(set as *const HashSet<V, S> as *const Self) }
+    }
+
+    /// Views an existing [`hashbrown::HashSet`] as a mutable `HashableHashSet` without copying
+    /// it.
+    #[inline]
+    pub fn from_mut(set: &mut HashSet<V, S>) -> &mut Self {
+        // SAFETY: `HashableHashSet` is a transparent wrapper.
+        unsafe { &mut *(set as *mut HashSet<V, S> as *mut Self) }
+    }
+
+    /// Consumes the wrapper, returning the inner [`hashbrown::HashSet`].
+    #[inline]
+    pub fn into_inner(self) -> HashSet<V, S> {
+        self.0
+    }
+}
+
+impl<V: Debug, S> Debug for HashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V, S: Default> Default for HashableHashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableHashSet(HashSet::default())
+    }
+}
+
+impl<V, S> Deref for HashableHashSet<V, S> {
+    type Target = HashSet<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, S> DerefMut for HashableHashSet<V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher> Eq for HashableHashSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for HashableHashSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V, S> From<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn from(set: HashSet<V, S>) -> Self {
+        HashableHashSet(set)
+    }
+}
+
+impl<V, S> From<HashableHashSet<V, S>> for HashSet<V, S> {
+    #[inline]
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.0
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for HashableHashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableHashSet(HashSet::from_iter(iter))
+    }
+}
+
+impl<V: Hash, S> Hash for HashableHashSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(prehash), hasher);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a HashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = ::hashbrown::hash_set::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::hashbrown::hash_set::Iter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V, S> IntoIterator for HashableHashSet<V, S> {
+    type Item = V;
+    type IntoIter = ::hashbrown::hash_set::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> ::hashbrown::hash_set::IntoIter<V> {
+        self.0.into_iter()
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher> PartialEq for HashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// A [`hashbrown::HashMap`] wrapper that implements [`Hash`] like
+/// [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableHashMap<K, V, S = ahash::RandomState>(HashMap<K, V, S>);
+
+impl<K, V> HashableHashMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableHashMap<K, V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableHashMap<K, V> {
+        HashableHashMap::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableHashMap(HashMap::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableHashMap(HashMap::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Views an existing [`hashbrown::HashMap`] as a `HashableHashMap` without copying it.
+    #[inline]
+    pub fn from_ref(map: &HashMap<K, V, S>) -> &Self {
+        // SAFETY: `HashableHashMap` is a transparent wrapper.
+        unsafe { &*(map as *const HashMap<K, V, S> as *const Self) }
+    }
+
+    /// Views an existing [`hashbrown::HashMap`] as a mutable `HashableHashMap` without copying
+    /// it.
+    #[inline]
+    pub fn from_mut(map: &mut HashMap<K, V, S>) -> &mut Self {
+        // SAFETY: `HashableHashMap` is a transparent wrapper.
+        unsafe { &mut *(map as *mut HashMap<K, V, S> as *mut Self) }
+    }
+
+    /// Consumes the wrapper, returning the inner [`hashbrown::HashMap`].
+    #[inline]
+    pub fn into_inner(self) -> HashMap<K, V, S> {
+        self.0
+    }
+
+    /// Creates a raw entry builder for looking up, inserting, or removing an entry using a
+    /// precomputed hash. See [`hashbrown::HashMap::raw_entry_mut`].
+    #[inline]
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V, S> {
+        self.0.raw_entry_mut()
+    }
+
+    /// Creates a raw entry builder for looking up an entry using a precomputed hash. See
+    /// [`hashbrown::HashMap::raw_entry`].
+    #[inline]
+    pub fn raw_entry(&self) -> RawEntryBuilder<'_, K, V, S> {
+        self.0.raw_entry()
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for HashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: Default> Default for HashableHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableHashMap(HashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for HashableHashMap<K, V, S> {
+    type Target = HashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableHashMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableHashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashMap<K, V, S>) -> Self {
+        HashableHashMap(map)
+    }
+}
+
+impl<K, V, S> From<HashableHashMap<K, V, S>> for HashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        map.0
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashableHashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableHashMap(HashMap::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for HashableHashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = ::hashbrown::hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::hashbrown::hash_map::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K, V, S> IntoIterator for HashableHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = ::hashbrown::hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::hashbrown::hash_map::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> PartialEq for HashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod hashbrown_test {
+    use super::{HashableHashMap, HashableHashSet};
+    use crate::hash;
+    use ::hashbrown::hash_map::RawEntryMut;
+    use std::hash::BuildHasher;
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let set: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let std_set: crate::HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std_set));
+
+        let map: HashableHashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std_map));
+    }
+
+    #[test]
+    fn view_existing_collections() {
+        let mut map: ::hashbrown::HashMap<&str, u32, ahash::RandomState> = Default::default();
+        map.insert("a", 1);
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        assert_eq!(hash(HashableHashMap::from_ref(&map)), hash(&std_map));
+
+        HashableHashMap::from_mut(&mut map).insert("b", 2);
+        let set: ::hashbrown::HashSet<&str, ahash::RandomState> = map.keys().copied().collect();
+        let set: ::hashbrown::HashSet<&str, ahash::RandomState> = HashableHashSet::from(set).into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn dedup_with_precomputed_hash() {
+        let mut seen: HashableHashMap<String, usize> = HashableHashMap::new();
+        for word in ["a", "b", "a", "a"] {
+            let hash = seen.hasher().hash_one(word);
+            match seen.raw_entry_mut().from_key_hashed_nocheck(hash, word) {
+                RawEntryMut::Occupied(mut entry) => *entry.get_mut() += 1,
+                RawEntryMut::Vacant(entry) => {
+                    entry.insert_hashed_nocheck(hash, word.to_string(), 1);
+                }
+            }
+        }
+        assert_eq!(seen["a"], 3);
+        assert_eq!(seen["b"], 1);
+    }
+}
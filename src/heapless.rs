@@ -0,0 +1,258 @@
+//! Hashable wrappers around [`heapless`]'s fixed-capacity collections.
+//!
+//! [`HashableFnvIndexSet`] and [`HashableFnvIndexMap`] hash like the std wrappers, but sort their
+//! pre-hashes in a stack buffer sized by the capacity, so neither storing nor hashing them
+//! allocates. The crate itself still requires `std`, so they cannot be used on `no_std` firmware
+//! targets; they suit code that keeps fixed-capacity state on a `std` target.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::heapless::HashableFnvIndexSet;
+//!
+//! let mut a: HashableFnvIndexSet<u8, 4> = HashableFnvIndexSet::new();
+//! a.insert(1).unwrap();
+//! a.insert(2).unwrap();
+//! let b: HashableFnvIndexSet<u8, 4> = vec![2, 1].into_iter().collect();
+//! assert_eq!(a, b);
+//! ```
+
+use crate::prehash;
+use ::heapless::{FnvIndexMap, FnvIndexSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// Like [`crate::hash_prehashes`], but sorts at most `N` pre-hashes on the stack.
+fn hash_prehashes_inline<H: Hasher, const N: usize>(prehashes: impl Iterator<Item = u64>, hasher: &mut H) {
+    let mut buffer = [0u64; N];
+    let mut len = 0;
+    for (slot, hash) in buffer.iter_mut().zip(prehashes) {
+        *slot = hash;
+        len += 1;
+    }
+    buffer[..len].sort_unstable();
+    for hash in &buffer[..len] {
+        hasher.write_u64(*hash);
+    }
+}
+
+/// An [`FnvIndexSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableFnvIndexSet<V, const N: usize>(FnvIndexSet<V, N>);
+
+impl<V, const N: usize> HashableFnvIndexSet<V, N> {
+    #[inline]
+    pub const fn new() -> HashableFnvIndexSet<V, N> {
+        HashableFnvIndexSet(FnvIndexSet::new())
+    }
+
+    /// Consumes the wrapper, returning the inner [`FnvIndexSet`].
+    #[inline]
+    pub fn into_inner(self) -> FnvIndexSet<V, N> {
+        self.0
+    }
+}
+
+impl<V: Debug, const N: usize> Debug for HashableFnvIndexSet<V, N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V, const N: usize> Default for HashableFnvIndexSet<V, N> {
+    #[inline]
+    fn default() -> Self {
+        HashableFnvIndexSet::new()
+    }
+}
+
+impl<V, const N: usize> Deref for HashableFnvIndexSet<V, N> {
+    type Target = FnvIndexSet<V, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, const N: usize> DerefMut for HashableFnvIndexSet<V, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Eq + Hash, const N: usize> Eq for HashableFnvIndexSet<V, N> {}
+
+impl<V: Eq + Hash, const N: usize> Extend<V> for HashableFnvIndexSet<V, N> {
+    /// Panics if the set runs out of capacity, like [`FnvIndexSet`]'s own implementation.
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V, const N: usize> From<FnvIndexSet<V, N>> for HashableFnvIndexSet<V, N> {
+    #[inline]
+    fn from(set: FnvIndexSet<V, N>) -> Self {
+        HashableFnvIndexSet(set)
+    }
+}
+
+impl<V: Eq + Hash, const N: usize> FromIterator<V> for HashableFnvIndexSet<V, N> {
+    /// Panics if the iterator yields more than `N` distinct values.
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableFnvIndexSet(FnvIndexSet::from_iter(iter))
+    }
+}
+
+impl<V: Hash, const N: usize> Hash for HashableFnvIndexSet<V, N> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes_inline::<H, N>(self.0.iter().map(prehash), hasher);
+    }
+}
+
+impl<'a, V, const N: usize> IntoIterator for &'a HashableFnvIndexSet<V, N> {
+    type Item = &'a V;
+    type IntoIter = ::heapless::IndexSetIter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::heapless::IndexSetIter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V: Eq + Hash, const N: usize> PartialEq for HashableFnvIndexSet<V, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// An [`FnvIndexMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableFnvIndexMap<K, V, const N: usize>(FnvIndexMap<K, V, N>);
+
+impl<K, V, const N: usize> HashableFnvIndexMap<K, V, N> {
+    #[inline]
+    pub const fn new() -> HashableFnvIndexMap<K, V, N> {
+        HashableFnvIndexMap(FnvIndexMap::new())
+    }
+
+    /// Consumes the wrapper, returning the inner [`FnvIndexMap`].
+    #[inline]
+    pub fn into_inner(self) -> FnvIndexMap<K, V, N> {
+        self.0
+    }
+}
+
+impl<K: Debug, V: Debug, const N: usize> Debug for HashableFnvIndexMap<K, V, N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, const N: usize> Default for HashableFnvIndexMap<K, V, N> {
+    #[inline]
+    fn default() -> Self {
+        HashableFnvIndexMap::new()
+    }
+}
+
+impl<K, V, const N: usize> Deref for HashableFnvIndexMap<K, V, N> {
+    type Target = FnvIndexMap<K, V, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, const N: usize> DerefMut for HashableFnvIndexMap<K, V, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, const N: usize> Eq for HashableFnvIndexMap<K, V, N> {}
+
+impl<K: Eq + Hash, V, const N: usize> Extend<(K, V)> for HashableFnvIndexMap<K, V, N> {
+    /// Panics if the map runs out of capacity, like [`FnvIndexMap`]'s own implementation.
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, const N: usize> From<FnvIndexMap<K, V, N>> for HashableFnvIndexMap<K, V, N> {
+    #[inline]
+    fn from(map: FnvIndexMap<K, V, N>) -> Self {
+        HashableFnvIndexMap(map)
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> FromIterator<(K, V)> for HashableFnvIndexMap<K, V, N> {
+    /// Panics if the iterator yields more than `N` distinct keys.
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableFnvIndexMap(FnvIndexMap::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V: Hash, const N: usize> Hash for HashableFnvIndexMap<K, V, N> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes_inline::<H, N>(self.0.iter().map(|entry| prehash(&entry)), hasher);
+    }
+}
+
+impl<'a, K, V, const N: usize> IntoIterator for &'a HashableFnvIndexMap<K, V, N> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = ::heapless::IndexMapIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::heapless::IndexMapIter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K, V, const N: usize> IntoIterator for HashableFnvIndexMap<K, V, N> {
+    type Item = (K, V);
+    type IntoIter = <FnvIndexMap<K, V, N> as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, const N: usize> PartialEq for HashableFnvIndexMap<K, V, N> {
+    fn eq(&self, other: &Self) -> bool {
+        // `FnvIndexMap`'s own equality requires `V: Eq`.
+        self.0.len() == other.0.len() && self.0.iter().all(|(k, v)| other.0.get(k) == Some(v))
+    }
+}
+
+#[cfg(test)]
+mod heapless_test {
+    use super::{HashableFnvIndexMap, HashableFnvIndexSet};
+    use crate::{hash, HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let set: HashableFnvIndexSet<u32, 4> = vec![3, 1, 2].into_iter().collect();
+        let std: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std));
+
+        let map: HashableFnvIndexMap<u32, bool, 2> = vec![(1, true), (2, false)].into_iter().collect();
+        let std: HashableHashMap<u32, bool> = vec![(2, false), (1, true)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std));
+    }
+
+    #[test]
+    fn nested_sets() {
+        let mut inner: HashableFnvIndexSet<u8, 2> = HashableFnvIndexSet::new();
+        inner.insert(1).unwrap();
+        let mut outer: HashableFnvIndexSet<HashableFnvIndexSet<u8, 2>, 2> = HashableFnvIndexSet::new();
+        assert_eq!(outer.insert(inner.clone()), Ok(true));
+        assert_eq!(outer.insert(inner), Ok(false));
+    }
+}
@@ -0,0 +1,263 @@
+//! Hashable wrappers around [`im`]'s persistent hash collections, which share structure between
+//! clones so that cloning is cheap regardless of size.
+//!
+//! [`im`]'s own [`Hash`] implementations depend on iteration order, which varies with the hasher.
+//! These wrappers hash like [`crate::HashableHashSet`] and [`crate::HashableHashMap`] instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::im::HashableHashSet;
+//!
+//! let mut seen = std::collections::HashSet::new();
+//! let state: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+//! let mut next = state.clone();
+//! next.insert(4);
+//! assert!(seen.insert(state));
+//! assert!(seen.insert(next));
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::im::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// An [`im::HashSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableHashSet<V, S = ahash::RandomState>(HashSet<V, S>);
+
+impl<V> HashableHashSet<V> {
+    #[inline]
+    pub fn new() -> HashableHashSet<V> {
+        Default::default()
+    }
+}
+
+impl<V, S> HashableHashSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableHashSet(HashSet::with_hasher(hasher))
+    }
+
+    /// Consumes the wrapper, returning the inner [`im::HashSet`].
+    #[inline]
+    pub fn into_inner(self) -> HashSet<V, S> {
+        self.0
+    }
+}
+
+impl<V: Debug + Eq + Hash, S: BuildHasher> Debug for HashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V, S: BuildHasher + Default> Default for HashableHashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableHashSet(HashSet::default())
+    }
+}
+
+impl<V, S> Deref for HashableHashSet<V, S> {
+    type Target = HashSet<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, S> DerefMut for HashableHashSet<V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher + Default> Eq for HashableHashSet<V, S> {}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher> Extend<V> for HashableHashSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V, S> From<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn from(set: HashSet<V, S>) -> Self {
+        HashableHashSet(set)
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Default> FromIterator<V> for HashableHashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableHashSet(HashSet::from_iter(iter))
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Hash for HashableHashSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(prehash), hasher);
+    }
+}
+
+impl<'a, V: Eq + Hash, S: BuildHasher> IntoIterator for &'a HashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = ::im::hashset::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::im::hashset::Iter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher> IntoIterator for HashableHashSet<V, S> {
+    type Item = V;
+    type IntoIter = ::im::hashset::ConsumingIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> ::im::hashset::ConsumingIter<V> {
+        self.0.into_iter()
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher + Default> PartialEq for HashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// An [`im::HashMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableHashMap<K, V, S = ahash::RandomState>(HashMap<K, V, S>);
+
+impl<K, V> HashableHashMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableHashMap<K, V> {
+        Default::default()
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableHashMap(HashMap::with_hasher(hasher))
+    }
+
+    /// Consumes the wrapper, returning the inner [`im::HashMap`].
+    #[inline]
+    pub fn into_inner(self) -> HashMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Debug + Eq + Hash, V: Debug, S: BuildHasher> Debug for HashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: BuildHasher + Default> Default for HashableHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableHashMap(HashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for HashableHashMap<K, V, S> {
+    type Target = HashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableHashMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableHashMap<K, V, S> {}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BuildHasher> Extend<(K, V)> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashMap<K, V, S>) -> Self {
+        HashableHashMap(map)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BuildHasher + Default> FromIterator<(K, V)> for HashableHashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableHashMap(HashMap::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for HashableHashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> IntoIterator for &'a HashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = ::im::hashmap::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::im::hashmap::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BuildHasher> IntoIterator for HashableHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = ::im::hashmap::ConsumingIter<(K, V)>;
+
+    #[inline]
+    fn into_iter(self) -> ::im::hashmap::ConsumingIter<(K, V)> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> PartialEq for HashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod im_test {
+    use super::{HashableHashMap, HashableHashSet};
+    use crate::hash;
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let set: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let std_set: crate::HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std_set));
+
+        let map: HashableHashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std_map));
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let state: HashableHashMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        let mut next = state.clone();
+        next.insert("b", 2);
+        assert_eq!(state.len(), 1);
+        assert_ne!(hash(&state), hash(&next));
+    }
+}
@@ -0,0 +1,82 @@
+//! The subset-inclusion partial order.
+
+use crate::HashableHashSet;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, DerefMut};
+
+/// An adapter that orders sets by inclusion, so `ByInclusion(a) <= ByInclusion(b)` means `a` is a
+/// subset of `b`. Sets that are not subsets of one another are incomparable. Everything else is
+/// forwarded to the set.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::{ByInclusion, HashableHashSet};
+///
+/// let a: HashableHashSet<_> = vec![1].into_iter().collect();
+/// let b: HashableHashSet<_> = vec![1, 2].into_iter().collect();
+/// let c: HashableHashSet<_> = vec![3].into_iter().collect();
+/// assert!(ByInclusion(&a) < ByInclusion(&b));
+/// assert_eq!(ByInclusion(&a).partial_cmp(&ByInclusion(&c)), None);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByInclusion<T>(pub T);
+
+impl<T> Deref for ByInclusion<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ByInclusion<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+fn inclusion_cmp<V: Eq + Hash, S: BuildHasher>(a: &HashSet<V, S>, b: &HashSet<V, S>) -> Option<Ordering> {
+    match a.len().cmp(&b.len()) {
+        Ordering::Less if a.is_subset(b) => Some(Ordering::Less),
+        Ordering::Equal if a == b => Some(Ordering::Equal),
+        Ordering::Greater if b.is_subset(a) => Some(Ordering::Greater),
+        _ => None,
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialOrd for ByInclusion<HashableHashSet<V, S>> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        inclusion_cmp(&self.0, &other.0)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialOrd for ByInclusion<&HashableHashSet<V, S>> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        inclusion_cmp(self.0, other.0)
+    }
+}
+
+#[cfg(test)]
+mod inclusion_test {
+    use super::ByInclusion;
+    use crate::HashableHashSet;
+
+    #[test]
+    fn antichain_pruning() {
+        let sets: Vec<HashableHashSet<u32>> = vec![vec![1], vec![1, 2], vec![3], vec![2, 3], vec![1, 2]]
+            .into_iter()
+            .map(|v| v.into_iter().collect())
+            .collect();
+
+        // Keep only the maximal sets.
+        let maximal: Vec<_> = sets.iter()
+            .filter(|a| !sets.iter().any(|b| ByInclusion(*a) < ByInclusion(b)))
+            .collect();
+        assert_eq!(maximal.len(), 3);
+        assert!(maximal.iter().all(|s| s.len() == 2));
+        assert!(ByInclusion(sets[1].clone()) >= ByInclusion(sets[4].clone()));
+    }
+}
@@ -0,0 +1,432 @@
+//! Collections that maintain a running digest, so hashing them takes constant time.
+//!
+//! The digest is the wrapping sum of the entries' pre-hashes. Addition is commutative and can be
+//! undone by subtraction, so each insertion or removal updates it in constant time regardless of
+//! order. Because the digest is not computed like [`crate::HashableHashSet`]'s hash, the two
+//! produce different hashes for the same contents.
+
+use crate::prehash;
+use std::borrow::Borrow;
+use std::collections::{hash_map, hash_set, HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::Deref;
+
+/// A set that updates a digest of its elements on every insertion and removal, making [`Hash`]
+/// constant-time. Read access to the underlying [`HashSet`] is available through [`Deref`].
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::IncrementalHashableHashSet;
+///
+/// let mut a: IncrementalHashableHashSet<u32> = vec![1, 2].into_iter().collect();
+/// a.insert(3);
+/// a.remove(&1);
+/// let b: IncrementalHashableHashSet<u32> = vec![3, 2].into_iter().collect();
+/// assert_eq!(a.digest(), b.digest());
+/// assert_eq!(a, b);
+/// ```
+#[derive(Clone)]
+pub struct IncrementalHashableHashSet<V, S = ahash::RandomState> {
+    set: HashSet<V, S>,
+    digest: u64,
+}
+
+impl<V> IncrementalHashableHashSet<V> {
+    #[inline]
+    pub fn new() -> IncrementalHashableHashSet<V> {
+        Default::default()
+    }
+}
+
+impl<V, S> IncrementalHashableHashSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        IncrementalHashableHashSet { set: HashSet::with_hasher(hasher), digest: 0 }
+    }
+
+    /// Returns the running digest of the elements.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+
+    pub fn clear(&mut self) {
+        self.set.clear();
+        self.digest = 0;
+    }
+
+    /// Consumes the wrapper, returning the inner [`HashSet`].
+    #[inline]
+    pub fn into_inner(self) -> HashSet<V, S> {
+        self.set
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> IncrementalHashableHashSet<V, S> {
+    /// Adds a value, returning whether it was newly added.
+    pub fn insert(&mut self, value: V) -> bool {
+        let hash = prehash(&value);
+        let inserted = self.set.insert(value);
+        if inserted {
+            self.digest = self.digest.wrapping_add(hash);
+        }
+        inserted
+    }
+
+    /// Removes a value, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        match self.set.take(value) {
+            Some(removed) => {
+                self.digest = self.digest.wrapping_sub(prehash(&removed));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<V: Debug, S> Debug for IncrementalHashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.set.fmt(f) // transparent
+    }
+}
+
+impl<V, S: Default> Default for IncrementalHashableHashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        IncrementalHashableHashSet::with_hasher(S::default())
+    }
+}
+
+impl<V, S> Deref for IncrementalHashableHashSet<V, S> {
+    type Target = HashSet<V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl<'de, V, S> serde::Deserialize<'de> for IncrementalHashableHashSet<V, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashSet::<V, S>::deserialize(de).map(IncrementalHashableHashSet::from)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Eq for IncrementalHashableHashSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for IncrementalHashableHashSet<V, S> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<V: Hash, S> From<HashSet<V, S>> for IncrementalHashableHashSet<V, S> {
+    fn from(set: HashSet<V, S>) -> Self {
+        let digest = set.iter().map(prehash).fold(0, u64::wrapping_add);
+        IncrementalHashableHashSet { set, digest }
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for IncrementalHashableHashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        let mut set = IncrementalHashableHashSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<V, S> Hash for IncrementalHashableHashSet<V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_usize(self.set.len());
+        hasher.write_u64(self.digest);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a IncrementalHashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = hash_set::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> hash_set::Iter<'a, V> {
+        self.set.iter()
+    }
+}
+
+impl<V, S> IntoIterator for IncrementalHashableHashSet<V, S> {
+    type Item = V;
+    type IntoIter = hash_set::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> hash_set::IntoIter<V> {
+        self.set.into_iter()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialEq for IncrementalHashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        // Differing digests rule out equality without comparing elements.
+        self.digest == other.digest && self.set == other.set
+    }
+}
+
+impl<V: serde::Serialize, S> serde::Serialize for IncrementalHashableHashSet<V, S> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = crate::canonical::active() {
+            return crate::canonical::serialize_seq(encoding, self.set.iter(), ser);
+        }
+        ser.collect_seq(self.set.iter())
+    }
+}
+
+/// A map that updates a digest of its entries on every insertion and removal, making [`Hash`]
+/// constant-time. Read access to the underlying [`HashMap`] is available through [`Deref`];
+/// values can only be changed through methods that keep the digest up to date.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::IncrementalHashableHashMap;
+///
+/// let mut a: IncrementalHashableHashMap<&str, u32> = IncrementalHashableHashMap::new();
+/// a.insert("x", 1);
+/// a.update("x", |v| *v += 1);
+/// let b: IncrementalHashableHashMap<&str, u32> = vec![("x", 2)].into_iter().collect();
+/// assert_eq!(a.digest(), b.digest());
+/// ```
+#[derive(Clone)]
+pub struct IncrementalHashableHashMap<K, V, S = ahash::RandomState> {
+    map: HashMap<K, V, S>,
+    digest: u64,
+}
+
+impl<K, V> IncrementalHashableHashMap<K, V> {
+    #[inline]
+    pub fn new() -> IncrementalHashableHashMap<K, V> {
+        Default::default()
+    }
+}
+
+impl<K, V, S> IncrementalHashableHashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        IncrementalHashableHashMap { map: HashMap::with_hasher(hasher), digest: 0 }
+    }
+
+    /// Returns the running digest of the entries.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.digest = 0;
+    }
+
+    /// Consumes the wrapper, returning the inner [`HashMap`].
+    #[inline]
+    pub fn into_inner(self) -> HashMap<K, V, S> {
+        self.map
+    }
+}
+
+impl<K: Eq + Hash, V: Hash, S: BuildHasher> IncrementalHashableHashMap<K, V, S> {
+    /// Inserts a key-value pair, returning the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.digest = self.digest.wrapping_add(prehash(&(&key, &value)));
+        match self.map.entry(key) {
+            hash_map::Entry::Occupied(mut entry) => {
+                self.digest = self.digest.wrapping_sub(prehash(&(entry.key(), entry.get())));
+                Some(entry.insert(value))
+            }
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        let (key, value) = self.map.remove_entry(key)?;
+        self.digest = self.digest.wrapping_sub(prehash(&(&key, &value)));
+        Some(value)
+    }
+
+    /// Modifies the value for `key` in place, returning whether the key was present.
+    pub fn update<Q>(&mut self, key: &Q, f: impl FnOnce(&mut V)) -> bool
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        let (key, mut value) = match self.map.remove_entry(key) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        self.digest = self.digest.wrapping_sub(prehash(&(&key, &value)));
+        f(&mut value);
+        self.digest = self.digest.wrapping_add(prehash(&(&key, &value)));
+        self.map.insert(key, value);
+        true
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for IncrementalHashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.map.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: Default> Default for IncrementalHashableHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        IncrementalHashableHashMap::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> Deref for IncrementalHashableHashMap<K, V, S> {
+    type Target = HashMap<K, V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<'de, K, V, S> serde::Deserialize<'de> for IncrementalHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Deserialize<'de>,
+      V: Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashMap::<K, V, S>::deserialize(de).map(IncrementalHashableHashMap::from)
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for IncrementalHashableHashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V: Hash, S: BuildHasher> Extend<(K, V)> for IncrementalHashableHashMap<K, V, S> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash, V: Hash, S> From<HashMap<K, V, S>> for IncrementalHashableHashMap<K, V, S> {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        let digest = map.iter().map(|entry| prehash(&entry)).fold(0, u64::wrapping_add);
+        IncrementalHashableHashMap { map, digest }
+    }
+}
+
+impl<K: Eq + Hash, V: Hash, S: BuildHasher + Default> FromIterator<(K, V)> for IncrementalHashableHashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut map = IncrementalHashableHashMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> Hash for IncrementalHashableHashMap<K, V, S> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_usize(self.map.len());
+        hasher.write_u64(self.digest);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a IncrementalHashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> hash_map::Iter<'a, K, V> {
+        self.map.iter()
+    }
+}
+
+impl<K, V, S> IntoIterator for IncrementalHashableHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> hash_map::IntoIter<K, V> {
+        self.map.into_iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for IncrementalHashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        // Differing digests rule out equality without comparing entries.
+        self.digest == other.digest && self.map == other.map
+    }
+}
+
+impl<K, V, S> serde::Serialize for IncrementalHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Serialize,
+      V: serde::Serialize,
+      S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = crate::canonical::active() {
+            return crate::canonical::serialize_map(encoding, self.map.iter(), ser);
+        }
+        ser.collect_map(self.map.iter())
+    }
+}
+
+#[cfg(test)]
+mod incremental_test {
+    use crate::{hash, IncrementalHashableHashMap, IncrementalHashableHashSet};
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn digest_tracks_mutations() {
+        let mut set: IncrementalHashableHashSet<u32> = IncrementalHashableHashSet::new();
+        for i in 0..10 {
+            set.insert(i);
+        }
+        for i in 0..5 {
+            set.remove(&i);
+        }
+        assert!(!set.insert(9));
+        let rebuilt = IncrementalHashableHashSet::from((5..10).collect::<HashSet<u32, ahash::RandomState>>());
+        assert_eq!(set.digest(), rebuilt.digest());
+        assert_eq!(hash(&set), hash(&rebuilt));
+        assert_eq!(set, rebuilt);
+    }
+
+    #[test]
+    fn map_digest_tracks_replaced_values() {
+        let mut map: IncrementalHashableHashMap<&str, u32> = IncrementalHashableHashMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        assert_eq!(map.insert("a", 3), Some(1));
+        assert!(map.update("b", |v| *v *= 2));
+        assert!(!map.update("c", |v| *v = 0));
+        assert_eq!(map.remove("c"), None);
+
+        let rebuilt = IncrementalHashableHashMap::from(
+            vec![("a", 3), ("b", 4)].into_iter().collect::<HashMap<_, _, ahash::RandomState>>());
+        assert_eq!(map.digest(), rebuilt.digest());
+        assert_eq!(map, rebuilt);
+    }
+}
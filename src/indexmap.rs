@@ -0,0 +1,332 @@
+//! Hashable wrappers around [`indexmap`]'s insertion-ordered collections.
+//!
+//! Like the std wrappers, [`HashableIndexSet`] and [`HashableIndexMap`] hash and compare
+//! independently of order, matching [`indexmap`]'s own equality. Wrap one in [`Ordered`] to make
+//! insertion order significant to both hashing and equality instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::indexmap::{HashableIndexSet, Ordered};
+//!
+//! let a: HashableIndexSet<u32> = vec![1, 2].into_iter().collect();
+//! let b: HashableIndexSet<u32> = vec![2, 1].into_iter().collect();
+//! assert_eq!(a, b);
+//! assert_ne!(Ordered(a), Ordered(b));
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::indexmap::{IndexMap, IndexSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// An [`IndexSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableIndexSet<V, S = ahash::RandomState>(IndexSet<V, S>);
+
+impl<V> HashableIndexSet<V> {
+    #[inline]
+    pub fn new() -> HashableIndexSet<V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableIndexSet<V> {
+        HashableIndexSet::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<V, S> HashableIndexSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableIndexSet(IndexSet::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableIndexSet(IndexSet::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Consumes the wrapper, returning the inner [`IndexSet`].
+    #[inline]
+    pub fn into_inner(self) -> IndexSet<V, S> {
+        self.0
+    }
+}
+
+impl<V: Debug, S> Debug for HashableIndexSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V, S: Default> Default for HashableIndexSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableIndexSet(IndexSet::default())
+    }
+}
+
+impl<V, S> Deref for HashableIndexSet<V, S> {
+    type Target = IndexSet<V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V, S> DerefMut for HashableIndexSet<V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher> Eq for HashableIndexSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for HashableIndexSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V, S> From<IndexSet<V, S>> for HashableIndexSet<V, S> {
+    #[inline]
+    fn from(set: IndexSet<V, S>) -> Self {
+        HashableIndexSet(set)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for HashableIndexSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableIndexSet(IndexSet::from_iter(iter))
+    }
+}
+
+impl<V: Hash, S> Hash for HashableIndexSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(prehash), hasher);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a HashableIndexSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = ::indexmap::set::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::indexmap::set::Iter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V, S> IntoIterator for HashableIndexSet<V, S> {
+    type Item = V;
+    type IntoIter = ::indexmap::set::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> ::indexmap::set::IntoIter<V> {
+        self.0.into_iter()
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher> PartialEq for HashableIndexSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// An [`IndexMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableIndexMap<K, V, S = ahash::RandomState>(IndexMap<K, V, S>);
+
+impl<K, V> HashableIndexMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableIndexMap<K, V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableIndexMap<K, V> {
+        HashableIndexMap::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<K, V, S> HashableIndexMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableIndexMap(IndexMap::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableIndexMap(IndexMap::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Consumes the wrapper, returning the inner [`IndexMap`].
+    #[inline]
+    pub fn into_inner(self) -> IndexMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for HashableIndexMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: Default> Default for HashableIndexMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableIndexMap(IndexMap::default())
+    }
+}
+
+impl<K, V, S> Deref for HashableIndexMap<K, V, S> {
+    type Target = IndexMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableIndexMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableIndexMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for HashableIndexMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> From<IndexMap<K, V, S>> for HashableIndexMap<K, V, S> {
+    #[inline]
+    fn from(map: IndexMap<K, V, S>) -> Self {
+        HashableIndexMap(map)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashableIndexMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableIndexMap(IndexMap::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for HashableIndexMap<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HashableIndexMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = ::indexmap::map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::indexmap::map::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K, V, S> IntoIterator for HashableIndexMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = ::indexmap::map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::indexmap::map::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> PartialEq for HashableIndexMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// An adapter that makes insertion order significant, so two collections are equal and hash alike
+/// only if they hold the same entries in the same order. Everything else is forwarded to the
+/// collection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ordered<T>(pub T);
+
+impl<T> Deref for Ordered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Ordered<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher> Eq for Ordered<HashableIndexSet<V, S>> {}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for Ordered<HashableIndexMap<K, V, S>> {}
+
+impl<V: Hash, S> Hash for Ordered<HashableIndexSet<V, S>> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_usize(self.0.len());
+        self.0.iter().for_each(|v| v.hash(hasher));
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for Ordered<HashableIndexMap<K, V, S>> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_usize(self.0.len());
+        self.0.iter().for_each(|entry| entry.hash(hasher));
+    }
+}
+
+impl<V: PartialEq, S> PartialEq for Ordered<HashableIndexSet<V, S>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.iter().eq(other.0.iter())
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, S> PartialEq for Ordered<HashableIndexMap<K, V, S>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.iter().eq(other.0.iter())
+    }
+}
+
+#[cfg(test)]
+mod indexmap_test {
+    use super::{HashableIndexMap, HashableIndexSet, Ordered};
+    use crate::hash;
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let set: HashableIndexSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let std_set: crate::HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std_set));
+
+        let map: HashableIndexMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std_map));
+    }
+
+    #[test]
+    fn ordered_mode() {
+        let a: HashableIndexMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let b: HashableIndexMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(a, b);
+        assert_ne!(Ordered(a.clone()), Ordered(b.clone()));
+        assert_ne!(hash(&Ordered(a.clone())), hash(&Ordered(b)));
+        assert_eq!(hash(&Ordered(a.clone())), hash(&Ordered(a)));
+    }
+}
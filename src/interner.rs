@@ -0,0 +1,208 @@
+//! Interning of hashable values behind handles with constant-time equality and hashing.
+
+use crate::prehash;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A registry that deduplicates equal values, such as [`crate::HashableHashSet`]s or
+/// [`crate::HashableHashMap`]s, and hands out [`Interned`] handles to them. Each value is hashed
+/// once, when it is interned: the interner stores that hash next to the value and never rehashes
+/// the value itself, not even when it grows.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::{HashableHashSet, Interner};
+///
+/// let mut interner = Interner::new();
+/// let a = interner.intern(HashableHashSet::from([1, 2]));
+/// let b = interner.intern(HashableHashSet::from([2, 1]));
+/// assert_eq!(a, b);
+/// assert_eq!(interner.len(), 1);
+/// assert!(a.contains(&1));
+/// ```
+pub struct Interner<T> {
+    /// The interned values, bucketed by their prehash. Buckets hold more than one value only when
+    /// distinct values collide.
+    values: HashMap<u64, Vec<Arc<T>>, ahash::RandomState>,
+    len: usize,
+}
+
+impl<T> Interner<T> {
+    #[inline]
+    pub fn new() -> Interner<T> {
+        Interner { values: HashMap::default(), len: 0 }
+    }
+
+    /// Returns the number of distinct values interned.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forgets values without any outstanding handles, returning how many were released.
+    pub fn purge(&mut self) -> usize {
+        let len = self.len;
+        self.values.retain(|_, bucket| {
+            bucket.retain(|value| Arc::strong_count(value) > 1);
+            !bucket.is_empty()
+        });
+        self.len = self.values.values().map(Vec::len).sum();
+        len - self.len
+    }
+}
+
+impl<T: Eq + Hash> Interner<T> {
+    /// Returns the handle for a value equal to `value`, interning `value` if there is none yet.
+    pub fn intern(&mut self, value: T) -> Interned<T> {
+        let bucket = self.values.entry(prehash(&value)).or_default();
+        if let Some(existing) = bucket.iter().find(|existing| ***existing == value) {
+            return Interned(Arc::clone(existing));
+        }
+        let value = Arc::new(value);
+        bucket.push(Arc::clone(&value));
+        self.len += 1;
+        Interned(value)
+    }
+
+    /// Returns the handle for a value equal to `value`, if one has been interned.
+    pub fn get(&self, value: &T) -> Option<Interned<T>> {
+        let existing = self.values.get(&prehash(value))?.iter().find(|existing| ***existing == *value)?;
+        Some(Interned(Arc::clone(existing)))
+    }
+}
+
+impl<T: Debug> Debug for Interner<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.values.values().flatten()).finish()
+    }
+}
+
+impl<T> Default for Interner<T> {
+    #[inline]
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+/// A handle to a value in an [`Interner`]. Equality and hashing use the handle's identity instead
+/// of the value, so both take constant time.
+///
+/// Handles from the same interner are equal exactly when their values are. Handles from different
+/// interners are never equal, and hashes depend on memory addresses, so they vary between runs.
+/// Read access to the value is available through [`Deref`].
+pub struct Interned<T>(Arc<T>);
+
+impl<T> Interned<T> {
+    /// Returns the interned value.
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Clone for Interned<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Interned(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Debug> Debug for Interned<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<T> Deref for Interned<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Eq for Interned<T> {}
+
+impl<T> Hash for Interned<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        Arc::as_ptr(&self.0).hash(hasher);
+    }
+}
+
+impl<T> PartialEq for Interned<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Interned<T> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod interner_test {
+    use crate::{HashableHashMap, HashableHashSet, Interner};
+
+    #[test]
+    fn deduplicates_equal_values() {
+        let mut interner = Interner::new();
+        let a = interner.intern(HashableHashMap::from([("x", 1), ("y", 2)]));
+        let b = interner.intern(HashableHashMap::from([("y", 2), ("x", 1)]));
+        let c = interner.intern(HashableHashMap::from([("x", 1)]));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(interner.get(&HashableHashMap::from([("x", 1)])), Some(c));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn purge_releases_unused_values() {
+        let mut interner = Interner::new();
+        let kept = interner.intern(HashableHashSet::from([1]));
+        drop(interner.intern(HashableHashSet::from([2])));
+        assert_eq!(interner.purge(), 1);
+        assert_eq!(interner.get(&HashableHashSet::from([1])), Some(kept));
+        assert_eq!(interner.get(&HashableHashSet::from([2])), None);
+    }
+
+    #[test]
+    fn hashes_each_value_once() {
+        use std::cell::Cell;
+        use std::hash::{Hash, Hasher};
+
+        #[derive(PartialEq, Eq)]
+        struct Counted<'a>(u32, &'a Cell<usize>);
+
+        impl Hash for Counted<'_> {
+            fn hash<H: Hasher>(&self, hasher: &mut H) {
+                self.1.set(self.1.get() + 1);
+                self.0.hash(hasher);
+            }
+        }
+
+        let first = Cell::new(0);
+        let others = Cell::new(0);
+        let mut interner = Interner::new();
+        interner.intern(Counted(0, &first));
+        for i in 1..1_000 {
+            interner.intern(Counted(i, &others));
+        }
+        assert_eq!(first.get(), 1);
+        assert_eq!(others.get(), 999);
+        assert_eq!(interner.len(), 1_000);
+    }
+}
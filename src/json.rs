@@ -0,0 +1,243 @@
+//! Hashable wrappers around [`serde_json`]'s dynamically typed values.
+//!
+//! [`HashableJsonValue`] hashes a JSON document structurally: arrays in order, and objects
+//! independently of key order, in the same way as [`crate::HashableHashMap`]. This lets arbitrary
+//! JSON documents be deduplicated in a [`crate::HashableHashSet`] or used as map keys, regardless
+//! of the `serde_json` version or whether its `preserve_order` feature is enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::json::HashableJsonValue;
+//! use hashable::HashableHashSet;
+//!
+//! let documents: HashableHashSet<HashableJsonValue> = vec![
+//!     serde_json::json!({"a": 1, "b": [true, null]}).into(),
+//!     serde_json::from_str::<HashableJsonValue>(r#"{"b": [true, null], "a": 1}"#).unwrap(),
+//! ].into_iter().collect();
+//! assert_eq!(documents.len(), 1);
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+
+/// A [`serde_json::Value`] wrapper that implements [`Hash`] structurally, ignoring the order of
+/// object keys.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[repr(transparent)]
+pub struct HashableJsonValue(Value);
+
+impl HashableJsonValue {
+    /// Views an existing [`serde_json::Value`] as a `HashableJsonValue` without copying it.
+    #[inline]
+    pub fn from_ref(value: &Value) -> &Self {
+        // SAFETY: `HashableJsonValue` is a transparent wrapper.
+        unsafe { &*(value as *const Value as *const Self) }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl Debug for HashableJsonValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl Deref for HashableJsonValue {
+    type Target = Value;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HashableJsonValue {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Value> for HashableJsonValue {
+    #[inline]
+    fn from(value: Value) -> Self {
+        HashableJsonValue(value)
+    }
+}
+
+impl From<HashableJsonValue> for Value {
+    #[inline]
+    fn from(value: HashableJsonValue) -> Self {
+        value.0
+    }
+}
+
+impl Hash for HashableJsonValue {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        match &self.0 {
+            Value::Null => hasher.write_u8(0),
+            Value::Bool(b) => {
+                hasher.write_u8(1);
+                b.hash(hasher);
+            }
+            Value::Number(n) => {
+                hasher.write_u8(2);
+                hash_number(n, hasher);
+            }
+            Value::String(s) => {
+                hasher.write_u8(3);
+                s.hash(hasher);
+            }
+            Value::Array(values) => {
+                hasher.write_u8(4);
+                hasher.write_usize(values.len());
+                for value in values {
+                    HashableJsonValue::from_ref(value).hash(hasher);
+                }
+            }
+            Value::Object(map) => {
+                hasher.write_u8(5);
+                HashableJsonMap::from_ref(map).hash(hasher);
+            }
+        }
+    }
+}
+
+/// Hashes a number consistently with its [`PartialEq`], under which integers and floats are never
+/// equal and `-0.0` equals `0.0`.
+fn hash_number<H: Hasher>(n: &Number, hasher: &mut H) {
+    if let Some(u) = n.as_u64() {
+        hasher.write_u8(0);
+        hasher.write_u64(u);
+    } else if let Some(i) = n.as_i64() {
+        hasher.write_u8(1);
+        hasher.write_i64(i);
+    } else if let Some(f) = n.as_f64() {
+        hasher.write_u8(2);
+        hasher.write_u64(if f == 0.0 { 0 } else { f.to_bits() });
+    }
+}
+
+/// A [`serde_json::Map`] wrapper that implements [`Hash`] independently of key order, like
+/// [`crate::HashableHashMap`].
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+#[repr(transparent)]
+pub struct HashableJsonMap(Map<String, Value>);
+
+impl HashableJsonMap {
+    #[inline]
+    pub fn new() -> HashableJsonMap {
+        Default::default()
+    }
+
+    /// Views an existing [`serde_json::Map`] as a `HashableJsonMap` without copying it.
+    #[inline]
+    pub fn from_ref(map: &Map<String, Value>) -> &Self {
+        // SAFETY: `HashableJsonMap` is a transparent wrapper.
+        unsafe { &*(map as *const Map<String, Value> as *const Self) }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.0
+    }
+}
+
+impl Debug for HashableJsonMap {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl Deref for HashableJsonMap {
+    type Target = Map<String, Value>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HashableJsonMap {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Map<String, Value>> for HashableJsonMap {
+    #[inline]
+    fn from(map: Map<String, Value>) -> Self {
+        HashableJsonMap(map)
+    }
+}
+
+impl From<HashableJsonMap> for Map<String, Value> {
+    #[inline]
+    fn from(map: HashableJsonMap) -> Self {
+        map.0
+    }
+}
+
+impl From<HashableJsonMap> for HashableJsonValue {
+    #[inline]
+    fn from(map: HashableJsonMap) -> Self {
+        HashableJsonValue(Value::Object(map.0))
+    }
+}
+
+impl Hash for HashableJsonMap {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(|(k, v)| prehash(&(k, HashableJsonValue::from_ref(v)))), hasher);
+    }
+}
+
+impl<'a> IntoIterator for &'a HashableJsonMap {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = serde_json::map::Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> serde_json::map::Iter<'a> {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod json_test {
+    use super::{HashableJsonMap, HashableJsonValue};
+    use crate::hash;
+    use serde_json::json;
+
+    #[test]
+    fn hash_ignores_key_order() {
+        let a: HashableJsonValue = serde_json::from_str(r#"{"x": {"p": 1, "q": 2}, "y": [1, 2]}"#).unwrap();
+        let b: HashableJsonValue = serde_json::from_str(r#"{"y": [1, 2], "x": {"q": 2, "p": 1}}"#).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let c: HashableJsonValue = json!({"y": [2, 1], "x": {"q": 2, "p": 1}}).into();
+        assert_ne!(hash(&a), hash(&c));
+    }
+
+    #[test]
+    fn numbers_hash_consistently_with_eq() {
+        let zero: HashableJsonValue = json!(0.0).into();
+        let negative_zero: HashableJsonValue = json!(-0.0).into();
+        assert_eq!(zero, negative_zero);
+        assert_eq!(hash(&zero), hash(&negative_zero));
+
+        let map: HashableJsonMap = serde_json::from_str(r#"{"n": 1}"#).unwrap();
+        assert_eq!(hash(&HashableJsonValue::from(map)), hash(&HashableJsonValue::from(json!({"n": 1}))));
+    }
+}
@@ -0,0 +1,175 @@
+//! A map whose identity is determined by its keys alone.
+
+use crate::{hash_prehashes, prehash, HashableHashMap};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`HashableHashMap`] wrapper whose [`Hash`] and [`Eq`] consider only the keys, for maps whose
+/// values are caches or annotations that should not affect identity.
+///
+/// The hash equals that of a [`crate::HashableHashSet`] of the keys. To compare values as well,
+/// compare the underlying maps via [`Deref`] or [`KeyOnlyHashableHashMap::as_map`].
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::KeyOnlyHashableHashMap;
+///
+/// let a: KeyOnlyHashableHashMap<&str, u32> = vec![("x", 1), ("y", 2)].into_iter().collect();
+/// let b: KeyOnlyHashableHashMap<&str, u32> = vec![("y", 0), ("x", 0)].into_iter().collect();
+/// assert_eq!(a, b);
+/// assert_ne!(a.as_map(), b.as_map());
+/// ```
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct KeyOnlyHashableHashMap<K, V, S = ahash::RandomState>(HashableHashMap<K, V, S>);
+
+impl<K, V> KeyOnlyHashableHashMap<K, V> {
+    #[inline]
+    pub fn new() -> KeyOnlyHashableHashMap<K, V> {
+        KeyOnlyHashableHashMap(HashableHashMap::new())
+    }
+}
+
+impl<K, V, S> KeyOnlyHashableHashMap<K, V, S> {
+    /// Views an existing [`HashableHashMap`] as a `KeyOnlyHashableHashMap` without copying it.
+    #[inline]
+    pub fn from_ref(map: &HashableHashMap<K, V, S>) -> &Self {
+        // SAFETY: `KeyOnlyHashableHashMap` is a transparent wrapper.
+        unsafe { &*(map as *const HashableHashMap<K, V, S> as *const Self) }
+    }
+
+    /// Returns the underlying map, whose equality also compares values.
+    #[inline]
+    pub fn as_map(&self) -> &HashableHashMap<K, V, S> {
+        &self.0
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> HashableHashMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for KeyOnlyHashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S: Default> Default for KeyOnlyHashableHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        KeyOnlyHashableHashMap(HashableHashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for KeyOnlyHashableHashMap<K, V, S> {
+    type Target = HashableHashMap<K, V, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for KeyOnlyHashableHashMap<K, V, S> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'de, K, V, S> serde::Deserialize<'de> for KeyOnlyHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Deserialize<'de>,
+      V: serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashMap::deserialize(de).map(KeyOnlyHashableHashMap)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Eq for KeyOnlyHashableHashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for KeyOnlyHashableHashMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl<K, V, S> From<HashableHashMap<K, V, S>> for KeyOnlyHashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        KeyOnlyHashableHashMap(map)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for KeyOnlyHashableHashMap<K, V, S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        KeyOnlyHashableHashMap(HashableHashMap::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V, S> Hash for KeyOnlyHashableHashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.keys().map(prehash), hasher);
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a KeyOnlyHashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::collections::hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K, V, S> IntoIterator for KeyOnlyHashableHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = std::collections::hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> PartialEq for KeyOnlyHashableHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.keys().all(|k| other.0.contains_key(k))
+    }
+}
+
+impl<K, V, S> serde::Serialize for KeyOnlyHashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Serialize,
+      V: serde::Serialize,
+      S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod key_only_test {
+    use crate::{hash, HashableHashMap, HashableHashSet, KeyOnlyHashableHashMap};
+
+    #[test]
+    fn values_do_not_affect_identity() {
+        let mut a: KeyOnlyHashableHashMap<u32, String> = vec![(1, "one".to_string())].into_iter().collect();
+        let b = KeyOnlyHashableHashMap::from(HashableHashMap::from([(1, String::new())]));
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(hash(&a), hash(&HashableHashSet::from([1])));
+
+        a.insert(2, String::new());
+        assert_ne!(a, b);
+    }
+}
@@ -27,12 +27,115 @@
 //! outer_set.insert(inner_set);
 //! ```
 
+use std::borrow::{Borrow, BorrowMut};
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
-use std::fmt::{self, Debug, Formatter};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher, BuildHasher};
-use std::ops::{Deref, DerefMut};
-use std::iter::FromIterator;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Deref, DerefMut, Index, Sub, SubAssign,
+};
+use std::iter::{FromIterator, Sum};
+
+mod arc;
+#[cfg(feature = "bincode")]
+mod bincode_impls;
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impls;
+#[cfg(any(feature = "serde_json", feature = "ciborium"))]
+mod canonical;
+mod combinators;
+mod counter;
+pub mod crdt;
+#[cfg(feature = "dashmap")]
+pub mod dashmap;
+pub mod de;
+mod debug;
+mod default_map;
+pub mod diff;
+mod dyn_hashable;
+#[cfg(feature = "fixedbitset")]
+pub mod fixedbitset;
+mod float;
+mod frozen;
+#[cfg(feature = "memmap2")]
+mod frozen_file;
+pub mod hash_map;
+pub mod hash_set;
+#[cfg(feature = "hashbrown")]
+pub mod hashbrown;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "im")]
+pub mod im;
+mod inclusion;
+mod incremental;
+#[cfg(feature = "indexmap")]
+pub mod indexmap;
+mod interner;
+#[cfg(feature = "serde_json")]
+pub mod json;
+mod key_only;
+#[cfg(feature = "lru")]
+pub mod lru;
+mod macros;
+#[cfg(feature = "minicbor")]
+mod minicbor_impls;
+mod multimap;
+mod multiset;
+pub mod pairs;
+mod perfect;
+#[cfg(feature = "bincode")]
+mod persist;
+mod prehashed;
+pub mod prehashes;
+#[cfg(feature = "rkyv")]
+mod rkyv_impls;
+#[cfg(feature = "roaring")]
+pub mod roaring;
+#[cfg(feature = "schemars")]
+mod schemars_impls;
+mod similarity;
+pub mod sketch;
+#[cfg(feature = "slotmap")]
+pub mod slotmap;
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
+pub mod sorted;
+mod sorted_vec;
+#[cfg(feature = "speedy")]
+mod speedy_impls;
+mod stable;
+#[cfg(feature = "ts-rs")]
+mod ts_rs_impls;
+pub mod unordered;
+#[cfg(feature = "weak-table")]
+pub mod weak_table;
+
+pub use arc::{ArcHashableHashMap, ArcHashableHashSet};
+pub use counter::Count;
+pub use debug::SortedDebug;
+pub use default_map::HashableDefaultHashMap;
+pub use dyn_hashable::{DynHashable, DynHashableSet};
+pub use float::{HashableF32, HashableF64};
+pub use frozen::{FrozenHashableHashMap, FrozenHashableHashSet};
+#[cfg(feature = "memmap2")]
+pub use frozen_file::{FrozenSetFile, FrozenSetFileError};
+pub use inclusion::ByInclusion;
+pub use incremental::{IncrementalHashableHashMap, IncrementalHashableHashSet};
+pub use interner::{Interned, Interner};
+pub use key_only::KeyOnlyHashableHashMap;
+pub use multimap::HashableMultiMap;
+pub use multiset::HashableMultiSet;
+#[cfg(feature = "bincode")]
+pub use persist::PersistError;
+pub use perfect::PerfectHashableHashSet;
+pub use prehashed::PrehashedHashableHashSet;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impls::{ArchivedHashableHashMap, ArchivedHashableHashSet};
+pub use sorted::Sorted;
+pub use sorted_vec::{HashableVecMap, HashableVecSet};
 
 // Reuse a buffer to avoid temporary allocations.
 thread_local!(static BUFFER: RefCell<Vec<u64>> = RefCell::new(Vec::with_capacity(100)));
@@ -40,8 +143,41 @@ thread_local!(static BUFFER: RefCell<Vec<u64>> = RefCell::new(Vec::with_capacity
 /// A [`HashSet`] wrapper that implements [`Hash`] by sorting pre-hashed entries and feeding those back
 /// into the passed-in [`Hasher`].
 #[derive(Clone)]
+#[repr(transparent)]
 pub struct HashableHashSet<V, S = ahash::RandomState>(HashSet<V, S>);
 
+/// Hashes a single element independently of any particular [`BuildHasher`], so the result can be
+/// sorted to produce an order-independent digest.
+fn prehash<T: Hash + ?Sized>(value: &T) -> u64 {
+    if stable::active() {
+        let mut hasher = stable::StableHasher::default();
+        value.hash(&mut hasher);
+        return hasher.finish();
+    }
+    let mut hasher = ahash::AHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Feeds pre-hashed entries into `hasher` in sorted order, which makes the result independent of
+/// iteration order.
+fn hash_prehashes<H: Hasher>(prehashes: impl Iterator<Item = u64>, hasher: &mut H) {
+    BUFFER.with(|buffer| {
+        // The cached buffer might already be in use farther up the call stack, so the
+        // algorithm reverts to a fallback as needed.
+        let fallback = RefCell::new(Vec::new());
+
+        let mut buffer = buffer.try_borrow_mut()
+            .unwrap_or_else(|_| fallback.borrow_mut());
+        buffer.clear();
+        buffer.extend(prehashes);
+        buffer.sort_unstable();
+        for hash in &*buffer {
+            hasher.write_u64(*hash);
+        }
+    });
+}
+
 #[cfg(test)]
 fn hash<T: Hash>(value: &T) -> u64 {
     let mut hasher = ahash::AHasher::default();
@@ -54,6 +190,11 @@ impl<V> HashableHashSet<V> {
     pub fn new() -> HashableHashSet<V> {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableHashSet<V> {
+        HashableHashSet::with_capacity_and_hasher(capacity, Default::default())
+    }
 }
 
 impl<V, S> HashableHashSet<V, S> {
@@ -61,10 +202,186 @@ impl<V, S> HashableHashSet<V, S> {
     pub fn with_hasher(hasher: S) -> Self {
         HashableHashSet(HashSet::with_hasher(hasher))
     }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableHashSet(HashSet::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Views an existing [`HashSet`] as a `HashableHashSet` without copying it.
+    #[inline]
+    pub fn from_ref(set: &HashSet<V, S>) -> &Self {
+        // SAFETY: `HashableHashSet` is a transparent wrapper.
+        unsafe { &*(set as *const HashSet<V, S> as *const Self) }
+    }
+
+    /// Views an existing [`HashSet`] as a mutable `HashableHashSet` without copying it.
+    #[inline]
+    pub fn from_mut(set: &mut HashSet<V, S>) -> &mut Self {
+        // SAFETY: `HashableHashSet` is a transparent wrapper.
+        unsafe { &mut *(set as *mut HashSet<V, S> as *mut Self) }
+    }
+
+    /// Consumes the wrapper, returning the inner [`HashSet`].
+    #[inline]
+    pub fn into_inner(self) -> HashSet<V, S> {
+        self.0
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> HashableHashSet<V, S> {
+    /// Inserts every value, returning how many were not already present.
+    pub fn insert_all<I: IntoIterator<Item=V>>(&mut self, values: I) -> usize {
+        values.into_iter().map(|v| self.0.insert(v) as usize).sum()
+    }
+
+    /// Returns whether every value is in the set.
+    pub fn contains_all<'a, Q, I>(&self, values: I) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized + 'a,
+          I: IntoIterator<Item=&'a Q>,
+    {
+        values.into_iter().all(|v| self.0.contains(v))
+    }
+
+    /// Removes and returns an arbitrary element, or [`None`] if the set is empty.
+    pub fn pop(&mut self) -> Option<V>
+    where V: Clone,
+    {
+        let value = self.0.iter().next()?.clone();
+        self.0.take(&value)
+    }
+
+    /// Inserts a value unless an equal value is already present, in which case the set is left
+    /// unchanged and the value is returned as the error.
+    pub fn try_insert(&mut self, value: V) -> Result<(), V> {
+        if self.0.contains(&value) {
+            return Err(value);
+        }
+        self.0.insert(value);
+        Ok(())
+    }
+
+    /// Lazily removes and yields the elements for which `pred` returns `true`. Elements that are
+    /// not yielded because the iterator is dropped early remain in the set.
+    #[cfg(feature = "nightly")]
+    pub fn extract_if<F>(&mut self, pred: F) -> std::collections::hash_set::ExtractIf<'_, V, F>
+    where F: FnMut(&V) -> bool,
+    {
+        self.0.extract_if(pred)
+    }
+}
+
+impl<V: Ord + Clone, S> HashableHashSet<V, S> {
+    /// Returns a [`BTreeSet`] with clones of the elements, which iterates in sorted order.
+    pub fn to_btree_set(&self) -> BTreeSet<V> {
+        self.0.iter().cloned().collect()
+    }
+}
+
+impl<V, S> AsMut<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut HashSet<V, S> {
+        &mut self.0
+    }
+}
+
+impl<V, S> AsRef<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn as_ref(&self) -> &HashSet<V, S> {
+        &self.0
+    }
+}
+
+impl<V, S> BitAnd<&HashableHashSet<V, S>> for &HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Default,
+{
+    type Output = HashableHashSet<V, S>;
+
+    /// Returns the intersection of `self` and `rhs` as a new set.
+    fn bitand(self, rhs: &HashableHashSet<V, S>) -> HashableHashSet<V, S> {
+        self.0.intersection(&rhs.0).cloned().collect()
+    }
+}
+
+impl<V, S> BitAndAssign<&HashableHashSet<V, S>> for HashableHashSet<V, S>
+where V: Eq + Hash,
+      S: BuildHasher,
+{
+    /// Retains only the elements that are also in `rhs`.
+    fn bitand_assign(&mut self, rhs: &HashableHashSet<V, S>) {
+        self.0.retain(|v| rhs.0.contains(v));
+    }
+}
+
+impl<V, S> BitOr<&HashableHashSet<V, S>> for &HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Default,
+{
+    type Output = HashableHashSet<V, S>;
+
+    /// Returns the union of `self` and `rhs` as a new set.
+    fn bitor(self, rhs: &HashableHashSet<V, S>) -> HashableHashSet<V, S> {
+        self.0.union(&rhs.0).cloned().collect()
+    }
+}
+
+impl<V, S> BitOrAssign<&HashableHashSet<V, S>> for HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher,
+{
+    /// Adds the elements of `rhs`.
+    fn bitor_assign(&mut self, rhs: &HashableHashSet<V, S>) {
+        self.0.extend(rhs.0.iter().cloned());
+    }
+}
+
+impl<V, S> BitXor<&HashableHashSet<V, S>> for &HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Default,
+{
+    type Output = HashableHashSet<V, S>;
+
+    /// Returns the symmetric difference of `self` and `rhs` as a new set.
+    fn bitxor(self, rhs: &HashableHashSet<V, S>) -> HashableHashSet<V, S> {
+        self.0.symmetric_difference(&rhs.0).cloned().collect()
+    }
+}
+
+impl<V, S> BitXorAssign<&HashableHashSet<V, S>> for HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher,
+{
+    /// Keeps the elements that are in exactly one of `self` and `rhs`.
+    fn bitxor_assign(&mut self, rhs: &HashableHashSet<V, S>) {
+        for v in &rhs.0 {
+            if !self.0.remove(v) {
+                self.0.insert(v.clone());
+            }
+        }
+    }
+}
+
+impl<V, S> Borrow<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn borrow(&self) -> &HashSet<V, S> {
+        &self.0
+    }
+}
+
+impl<V, S> BorrowMut<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut HashSet<V, S> {
+        &mut self.0
+    }
 }
 
 impl<V: Debug, S> Debug for HashableHashSet<V, S> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if debug::active() {
+            return debug::fmt_set(self.0.iter(), f);
+        }
         self.0.fmt(f) // transparent
     }
 }
@@ -90,34 +407,106 @@ impl<V, S> DerefMut for HashableHashSet<V, S> {
     }
 }
 
+/// Renders the set as `{a, b, c}`, with elements sorted by their rendered text so the output does
+/// not depend on the hasher or insertion order.
+impl<V: Display, S> Display for HashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut elements: Vec<String> = self.0.iter().map(|v| v.to_string()).collect();
+        elements.sort_unstable();
+        write!(f, "{{{}}}", elements.join(", "))
+    }
+}
+
 impl<V: Hash + Eq, S: BuildHasher> Eq for HashableHashSet<V, S> {}
 
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for HashableHashSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a, V: Eq + Hash + Copy + 'a, S: BuildHasher> Extend<&'a V> for HashableHashSet<V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=&'a V>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<V: Eq + Hash, const N: usize> From<[V; N]> for HashableHashSet<V> {
+    fn from(array: [V; N]) -> Self {
+        IntoIterator::into_iter(array).collect()
+    }
+}
+
+impl<V: Eq + Hash> From<BTreeSet<V>> for HashableHashSet<V> {
+    fn from(set: BTreeSet<V>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+impl<V: Ord, S> From<HashableHashSet<V, S>> for BTreeSet<V> {
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.0.into_iter().collect()
+    }
+}
+
+impl<V: Eq + Hash> From<Box<[V]>> for HashableHashSet<V> {
+    fn from(elements: Box<[V]>) -> Self {
+        elements.into_vec().into_iter().collect()
+    }
+}
+
+impl<V: Eq + Hash> From<Vec<V>> for HashableHashSet<V> {
+    fn from(elements: Vec<V>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+/// Converts a unit-valued map into the set of its keys, keeping the map's hasher.
+impl<V, S> From<HashableHashMap<V, (), S>> for HashableHashSet<V, S>
+where V: Eq + Hash,
+      S: BuildHasher + Clone,
+{
+    fn from(map: HashableHashMap<V, (), S>) -> Self {
+        let mut set = HashableHashSet::with_capacity_and_hasher(map.len(), map.hasher().clone());
+        set.extend(map.0.into_keys());
+        set
+    }
+}
+
+impl<V, S> From<HashSet<V, S>> for HashableHashSet<V, S> {
+    #[inline]
+    fn from(set: HashSet<V, S>) -> Self {
+        HashableHashSet(set)
+    }
+}
+
+impl<V, S> From<HashableHashSet<V, S>> for HashSet<V, S> {
+    #[inline]
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.0
+    }
+}
+
 impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for HashableHashSet<V, S> {
     fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
         HashableHashSet(HashSet::from_iter(iter))
     }
 }
 
+impl<'a, V, S> FromIterator<&'a V> for HashableHashSet<V, S>
+where V: Eq + Hash + Clone + 'a,
+      S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item=&'a V>>(iter: T) -> Self {
+        HashableHashSet(iter.into_iter().cloned().collect())
+    }
+}
+
 impl<V: Hash, S> Hash for HashableHashSet<V, S> {
     fn hash<H: Hasher>(&self, hasher: &mut H) {
-        BUFFER.with(|buffer| {
-            // The cached buffer might already be in use farther up the call stack, so the
-            // algorithm reverts to a fallback as needed.
-            let fallback = RefCell::new(Vec::new());
-
-            let mut buffer = buffer.try_borrow_mut()
-                .unwrap_or_else(|_| fallback.borrow_mut());
-            buffer.clear();
-            buffer.extend(self.0.iter().map(|v| {
-                let mut inner_hasher = ahash::AHasher::default();
-                v.hash(&mut inner_hasher);
-                inner_hasher.finish()
-            }));
-            buffer.sort_unstable();
-            for v in &*buffer {
-                hasher.write_u64(*v);
-            }
-        });
+        hash_prehashes(self.0.iter().map(prehash), hasher);
     }
 }
 
@@ -131,25 +520,139 @@ impl<'a, V, S> IntoIterator for &'a HashableHashSet<V, S> {
     }
 }
 
+impl<V, S> IntoIterator for HashableHashSet<V, S> {
+    type Item = V;
+    type IntoIter = std::collections::hash_set::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_set::IntoIter<V> {
+        self.0.into_iter()
+    }
+}
+
+/// Orders sets by lexicographically comparing their elements in sorted order, which is how
+/// [`BTreeSet`] is ordered. The order is total and consistent with
+/// [`Eq`] but otherwise arbitrary; it does not reflect inclusion.
+impl<V: Ord + Hash, S: BuildHasher> Ord for HashableHashSet<V, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut a: Vec<&V> = self.0.iter().collect();
+        let mut b: Vec<&V> = other.0.iter().collect();
+        a.sort_unstable();
+        b.sort_unstable();
+        a.cmp(&b)
+    }
+}
+
 impl<V: Hash + Eq, S: BuildHasher> PartialEq for HashableHashSet<V, S> {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
     }
 }
 
+impl<V: Ord + Hash, S: BuildHasher> PartialOrd for HashableHashSet<V, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares sets that may use different hashers.
+fn sets_eq<V: Hash + Eq, S1: BuildHasher, S2: BuildHasher>(a: &HashSet<V, S1>, b: &HashSet<V, S2>) -> bool {
+    a.len() == b.len() && a.iter().all(|v| b.contains(v))
+}
+
+impl<V: Hash + Eq, S: BuildHasher, S2: BuildHasher> PartialEq<HashSet<V, S2>> for HashableHashSet<V, S> {
+    fn eq(&self, other: &HashSet<V, S2>) -> bool {
+        sets_eq(&self.0, other)
+    }
+}
+
+impl<V: Hash + Eq, S: BuildHasher, S2: BuildHasher> PartialEq<HashableHashSet<V, S>> for HashSet<V, S2> {
+    fn eq(&self, other: &HashableHashSet<V, S>) -> bool {
+        sets_eq(self, &other.0)
+    }
+}
+
+impl<V, S> Sub<&HashableHashSet<V, S>> for &HashableHashSet<V, S>
+where V: Eq + Hash + Clone,
+      S: BuildHasher + Default,
+{
+    type Output = HashableHashSet<V, S>;
+
+    /// Returns the difference of `self` and `rhs` as a new set.
+    fn sub(self, rhs: &HashableHashSet<V, S>) -> HashableHashSet<V, S> {
+        self.0.difference(&rhs.0).cloned().collect()
+    }
+}
+
+impl<V, S> SubAssign<&HashableHashSet<V, S>> for HashableHashSet<V, S>
+where V: Eq + Hash,
+      S: BuildHasher,
+{
+    /// Removes the elements of `rhs`.
+    fn sub_assign(&mut self, rhs: &HashableHashSet<V, S>) {
+        if rhs.len() < self.len() {
+            for v in &rhs.0 {
+                self.0.remove(v);
+            }
+        } else {
+            self.0.retain(|v| !rhs.0.contains(v));
+        }
+    }
+}
+
+/// Folds sets by union.
+impl<V: Eq + Hash, S: BuildHasher + Default> Sum for HashableHashSet<V, S> {
+    fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+        iter.fold(HashableHashSet::default(), |mut acc, mut set| {
+            // Extend the larger set with the smaller one.
+            if acc.len() < set.len() {
+                std::mem::swap(&mut acc, &mut set);
+            }
+            acc.extend(set);
+            acc
+        })
+    }
+}
+
 impl<V, S> serde::Serialize for HashableHashSet<V, S>
 where V: Eq + Hash + serde::Serialize,
       S: BuildHasher,
 {
     fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = canonical::active() {
+            return canonical::serialize_seq(encoding, self.0.iter(), ser);
+        }
         self.0.serialize(ser)
     }
 }
 
+impl<'de, V, S> serde::Deserialize<'de> for HashableHashSet<V, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashSet::deserialize(de).map(HashableHashSet)
+    }
+}
+
+impl<'de, V, S, E> serde::de::IntoDeserializer<'de, E> for HashableHashSet<V, S>
+where V: Eq + Hash + serde::de::IntoDeserializer<'de, E>,
+      S: BuildHasher,
+      E: serde::de::Error,
+{
+    type Deserializer = <HashSet<V, S> as serde::de::IntoDeserializer<'de, E>>::Deserializer;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self.0.into_deserializer()
+    }
+}
+
 #[cfg(test)]
 mod hashable_hash_set_test {
     use crate::hash;
-    use super::HashableHashSet;
+    use super::{HashableHashMap, HashableHashSet};
+    use std::collections::{BTreeSet, HashSet};
 
     #[test]
     fn different_hash_if_items_differ() {
@@ -196,11 +699,247 @@ mod hashable_hash_set_test {
         });
         hash(&set); // No assertion as this test is just checking for a panic.
     }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut set = HashableHashSet::new();
+        set.insert({
+            let mut set = HashableHashSet::new();
+            set.insert("value".to_string());
+            set
+        });
+
+        let json = serde_json::to_string(&set).unwrap();
+        let round_tripped: HashableHashSet<HashableHashSet<String>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(set, round_tripped);
+    }
+
+    #[test]
+    fn into_iter_by_value() {
+        let set: HashableHashSet<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let mut values: Vec<String> = Vec::new();
+        for v in set {
+            values.push(v);
+        }
+        values.sort();
+        assert_eq!(values, ["a", "b"]);
+    }
+
+    #[test]
+    fn extend_generically() {
+        fn add_all<T: Extend<u32>>(target: &mut T) {
+            target.extend(vec![1, 2, 2, 3]);
+        }
+
+        let mut set = HashableHashSet::new();
+        add_all(&mut set);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn extend_from_refs() {
+        let mut set: HashableHashSet<u32> = HashableHashSet::new();
+        set.extend([1, 2, 3].iter().filter(|&&v| v != 2));
+        assert!(set.contains(&1) && set.contains(&3));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn collect_from_refs() {
+        let names = ["a".to_string(), "bb".to_string(), "cc".to_string()];
+        let set: HashableHashSet<String> = names.iter().filter(|n| n.len() == 2).collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("bb"));
+    }
+
+    #[test]
+    fn as_ref_to_std() {
+        fn total<T: AsRef<HashSet<u32, ahash::RandomState>>>(set: T) -> u32 {
+            set.as_ref().iter().sum()
+        }
+
+        let mut set: HashableHashSet<u32> = vec![1, 2].into_iter().collect();
+        set.as_mut().insert(3);
+        assert_eq!(total(&set), 6);
+    }
+
+    #[test]
+    fn eq_std() {
+        let set: HashableHashSet<u32> = vec![1, 2].into_iter().collect();
+        let std_set: HashSet<u32> = vec![2, 1].into_iter().collect();
+        assert_eq!(set, std_set);
+        assert_eq!(std_set, set);
+        assert_ne!(set, HashSet::new());
+    }
+
+    #[test]
+    fn sum_is_union() {
+        let sets = vec![vec![1, 2], vec![2, 3, 4], vec![]];
+        let union: HashableHashSet<u32> = sets.into_iter().map(|v| v.into_iter().collect::<HashableHashSet<_>>()).sum();
+        assert_eq!(union, vec![1, 2, 3, 4].into_iter().collect::<HashSet<_>>());
+    }
+
+    #[test]
+    fn set_operators() {
+        let a: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let b: HashableHashSet<u32> = vec![2, 3, 4].into_iter().collect();
+        assert_eq!(&a | &b, vec![1, 2, 3, 4].into_iter().collect::<HashSet<_>>());
+        assert_eq!(&a & &b, vec![2, 3].into_iter().collect::<HashSet<_>>());
+        assert_eq!(&a ^ &b, vec![1, 4].into_iter().collect::<HashSet<_>>());
+        assert_eq!(&a - &b, vec![1].into_iter().collect::<HashSet<_>>());
+
+        // The result is itself hashable.
+        hash(&(&a | &b));
+    }
+
+    #[test]
+    fn set_assign_operators() {
+        let a: HashableHashSet<u32> = vec![1, 2, 3].into_iter().collect();
+        let b: HashableHashSet<u32> = vec![2, 3, 4].into_iter().collect();
+        let mut set = a.clone();
+        set |= &b;
+        assert_eq!(set, &a | &b);
+        let mut set = a.clone();
+        set &= &b;
+        assert_eq!(set, &a & &b);
+        let mut set = a.clone();
+        set ^= &b;
+        assert_eq!(set, &a ^ &b);
+        let mut set = a.clone();
+        set -= &b;
+        assert_eq!(set, &a - &b);
+        // Removing elements needs no clones.
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Unique(u32);
+        let mut set: HashableHashSet<Unique> = vec![Unique(1), Unique(2), Unique(3)].into_iter().collect();
+        set &= &vec![Unique(2), Unique(3)].into_iter().collect();
+        set -= &vec![Unique(3)].into_iter().collect();
+        assert_eq!(set, vec![Unique(2)].into_iter().collect::<HashableHashSet<_>>());
+    }
+
+    #[test]
+    fn usable_as_btree_key() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(vec![2, 3].into_iter().collect::<HashableHashSet<u32>>(), "b");
+        map.insert(vec![3, 1].into_iter().collect::<HashableHashSet<u32>>(), "a");
+        map.insert(HashableHashSet::new(), "empty");
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), ["empty", "a", "b"]);
+    }
+
+    #[test]
+    fn display_is_sorted() {
+        let set: HashableHashSet<HashableHashSet<&str>> = vec![
+            vec!["b", "a"].into_iter().collect(),
+            HashableHashSet::new(),
+        ].into_iter().collect();
+        assert_eq!(set.to_string(), "{{a, b}, {}}");
+    }
+
+    #[test]
+    fn std_conversions() {
+        let std_set: HashSet<u32> = vec![1, 2].into_iter().collect();
+        let set = HashableHashSet::from(std_set.clone());
+        assert_eq!(set, std_set);
+        assert_eq!(HashSet::from(set.clone()), std_set);
+        assert_eq!(set.into_inner(), std_set);
+    }
+
+    #[test]
+    fn from_array() {
+        let set = HashableHashSet::from(["a", "b", "a"]);
+        assert_eq!(set, HashSet::from(["a", "b"]));
+    }
+
+    #[test]
+    fn from_vec_and_boxed_slice() {
+        let set = HashableHashSet::from(vec![1, 2, 2]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(HashableHashSet::from(vec![2, 1].into_boxed_slice()), set);
+    }
+
+    #[test]
+    fn btree_conversions() {
+        let btree = BTreeSet::from([3, 1, 2]);
+        let set = HashableHashSet::from(btree.clone());
+        assert_eq!(set.to_btree_set(), btree);
+        assert_eq!(BTreeSet::from(set), btree);
+    }
+
+    #[test]
+    fn from_ref_and_from_mut() {
+        let mut std_set: HashSet<u32, ahash::RandomState> = HashSet::default();
+        std_set.insert(1);
+        let expected = hash(&HashableHashSet::from(std_set.clone()));
+        assert_eq!(hash(HashableHashSet::from_ref(&std_set)), expected);
+
+        HashableHashSet::from_mut(&mut std_set).insert(2);
+        assert_eq!(std_set.len(), 2);
+    }
+
+    #[test]
+    fn unit_map_conversions() {
+        let set = HashableHashSet::from(["a", "b"]);
+        let map: HashableHashMap<&str, ()> = set.clone().into();
+        assert_eq!(map, HashableHashMap::from([("a", ()), ("b", ())]));
+        assert_eq!(HashableHashSet::from(map), set);
+    }
+
+    #[test]
+    fn bulk_helpers() {
+        let mut set = HashableHashSet::from(["a".to_string()]);
+        assert_eq!(set.insert_all(vec!["a".to_string(), "b".to_string(), "b".to_string()]), 1);
+        assert!(set.contains_all(["a", "b"].iter().copied()));
+        assert!(!set.contains_all(vec!["a", "c"]));
+        assert!(set.contains_all(Vec::<&str>::new()));
+    }
+
+    #[test]
+    fn pop_drains_worklist() {
+        let mut worklist = HashableHashSet::from([1, 2, 3]);
+        let mut seen = Vec::new();
+        while let Some(v) = worklist.pop() {
+            seen.push(v);
+            if v < 3 {
+                worklist.insert(v + 10);
+            }
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, [1, 2, 3, 11, 12]);
+    }
+
+    #[test]
+    fn try_insert() {
+        let mut set = HashableHashSet::new();
+        assert_eq!(set.try_insert("a".to_string()), Ok(()));
+        assert_eq!(set.try_insert("a".to_string()), Err("a".to_string()));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn pop() {
+        let mut set = HashableHashSet::from([1, 2]);
+        let mut popped = vec![set.pop().unwrap(), set.pop().unwrap()];
+        popped.sort_unstable();
+        assert_eq!(popped, [1, 2]);
+        assert_eq!(set.pop(), None);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn extract_if() {
+        let mut set = HashableHashSet::from([1, 2, 3, 4]);
+        let mut evens: Vec<u32> = set.extract_if(|v| v % 2 == 0).collect();
+        evens.sort_unstable();
+        assert_eq!(evens, [2, 4]);
+        assert_eq!(set, HashableHashSet::from([1, 3]));
+    }
 }
 
 /// A [`HashMap`] wrapper that implements [`Hash`] by sorting pre-hashed entries and feeding those back
 /// into the passed-in [`Hasher`].
 #[derive(Clone)]
+#[repr(transparent)]
 pub struct HashableHashMap<K, V, S = ahash::RandomState>(HashMap<K, V, S>);
 
 impl<K, V> HashableHashMap<K, V> {
@@ -208,6 +947,11 @@ impl<K, V> HashableHashMap<K, V> {
     pub fn new() -> HashableHashMap<K, V, ahash::RandomState> {
         Default::default()
     }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableHashMap<K, V, ahash::RandomState> {
+        HashableHashMap::with_capacity_and_hasher(capacity, Default::default())
+    }
 }
 
 impl<K, V, S> HashableHashMap<K, V, S> {
@@ -215,10 +959,118 @@ impl<K, V, S> HashableHashMap<K, V, S> {
     pub fn with_hasher(hasher: S) -> Self {
         HashableHashMap(HashMap::with_hasher(hasher))
     }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableHashMap(HashMap::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Views an existing [`HashMap`] as a `HashableHashMap` without copying it.
+    #[inline]
+    pub fn from_ref(map: &HashMap<K, V, S>) -> &Self {
+        // SAFETY: `HashableHashMap` is a transparent wrapper.
+        unsafe { &*(map as *const HashMap<K, V, S> as *const Self) }
+    }
+
+    /// Views an existing [`HashMap`] as a mutable `HashableHashMap` without copying it.
+    #[inline]
+    pub fn from_mut(map: &mut HashMap<K, V, S>) -> &mut Self {
+        // SAFETY: `HashableHashMap` is a transparent wrapper.
+        unsafe { &mut *(map as *mut HashMap<K, V, S> as *mut Self) }
+    }
+
+    /// Consumes the wrapper, returning the inner [`HashMap`].
+    #[inline]
+    pub fn into_inner(self) -> HashMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashableHashMap<K, V, S> {
+    /// Removes and returns an arbitrary entry, or [`None`] if the map is empty.
+    pub fn pop_entry(&mut self) -> Option<(K, V)>
+    where K: Clone,
+    {
+        let key = self.0.keys().next()?.clone();
+        self.0.remove_entry(&key)
+    }
+
+    /// Returns mutable references to the values for `N` distinct keys at once, or [`None`] if any
+    /// key is missing or the same key appears more than once.
+    pub fn get_many_mut<Q, const N: usize>(&mut self, keys: [&Q; N]) -> Option<[&mut V; N]>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        // Keys are compared by the address of the stored key, which, unlike the address of the
+        // value, is distinct per entry even when values are zero-sized.
+        let mut stored = [std::ptr::null::<K>(); N];
+        for (i, key) in keys.iter().enumerate() {
+            let (k, _) = self.0.get_key_value(*key)?;
+            if stored[..i].contains(&(k as *const K)) {
+                return None;
+            }
+            stored[i] = k;
+        }
+
+        let mut values = [std::ptr::null_mut::<V>(); N];
+        for (value, key) in values.iter_mut().zip(keys.iter()) {
+            *value = self.0.get_mut(*key)?;
+        }
+        // SAFETY: the pointers refer to the values of distinct entries, and the map stays mutably
+        // borrowed for as long as the references live.
+        Some(values.map(|value| unsafe { &mut *value }))
+    }
+
+    /// Lazily removes and yields the entries for which `pred` returns `true`. Entries that are
+    /// not yielded because the iterator is dropped early remain in the map.
+    #[cfg(feature = "nightly")]
+    pub fn extract_if<F>(&mut self, pred: F) -> std::collections::hash_map::ExtractIf<'_, K, V, F>
+    where F: FnMut(&K, &mut V) -> bool,
+    {
+        self.0.extract_if(pred)
+    }
+}
+
+impl<K: Ord + Clone, V: Clone, S> HashableHashMap<K, V, S> {
+    /// Returns a [`BTreeMap`] with clones of the entries, which iterates in key order.
+    pub fn to_btree_map(&self) -> BTreeMap<K, V> {
+        self.0.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+impl<K, V, S> AsMut<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut HashMap<K, V, S> {
+        &mut self.0
+    }
+}
+
+impl<K, V, S> AsRef<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn as_ref(&self) -> &HashMap<K, V, S> {
+        &self.0
+    }
+}
+
+impl<K, V, S> Borrow<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn borrow(&self) -> &HashMap<K, V, S> {
+        &self.0
+    }
+}
+
+impl<K, V, S> BorrowMut<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut HashMap<K, V, S> {
+        &mut self.0
+    }
 }
 
 impl<K: Debug, V: Debug, S> Debug for HashableHashMap<K, V, S> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if debug::active() {
+            return debug::fmt_map(self.0.iter(), f);
+        }
         self.0.fmt(f) // transparent
     }
 }
@@ -245,35 +1097,138 @@ impl<K, V, S> DerefMut for HashableHashMap<K, V, S> {
     }
 }
 
+/// Renders the map as `{k1: v1, k2: v2}`, with entries sorted by their rendered text so the
+/// output does not depend on the hasher or insertion order.
+impl<K: Display, V: Display, S> Display for HashableHashMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut entries: Vec<(String, String)> = self.0.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        entries.sort_unstable();
+        f.write_str("{")?;
+        for (i, (k, v)) in entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", k, v)?;
+        }
+        f.write_str("}")
+    }
+}
+
 impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableHashMap<K, V, S> {}
 
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a, K, V, S> Extend<(&'a K, &'a V)> for HashableHashMap<K, V, S>
+where K: Eq + Hash + Copy + 'a,
+      V: Copy + 'a,
+      S: BuildHasher,
+{
+    #[inline]
+    fn extend<T: IntoIterator<Item=(&'a K, &'a V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> From<[(K, V); N]> for HashableHashMap<K, V> {
+    fn from(array: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(array).collect()
+    }
+}
+
+impl<K: Eq + Hash, V> From<BTreeMap<K, V>> for HashableHashMap<K, V> {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<K: Ord, V, S> From<HashableHashMap<K, V, S>> for BTreeMap<K, V> {
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        map.0.into_iter().collect()
+    }
+}
+
+impl<K: Eq + Hash, V> From<Box<[(K, V)]>> for HashableHashMap<K, V> {
+    fn from(entries: Box<[(K, V)]>) -> Self {
+        entries.into_vec().into_iter().collect()
+    }
+}
+
+impl<K: Eq + Hash, V> From<Vec<(K, V)>> for HashableHashMap<K, V> {
+    fn from(entries: Vec<(K, V)>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+/// Converts a set into a unit-valued map, keeping the set's hasher.
+impl<K, S> From<HashableHashSet<K, S>> for HashableHashMap<K, (), S>
+where K: Eq + Hash,
+      S: BuildHasher + Clone,
+{
+    fn from(set: HashableHashSet<K, S>) -> Self {
+        let mut map = HashableHashMap::with_capacity_and_hasher(set.len(), set.hasher().clone());
+        map.extend(set.0.into_iter().map(|k| (k, ())));
+        map
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashMap<K, V, S>) -> Self {
+        HashableHashMap(map)
+    }
+}
+
+impl<K, V, S> From<HashableHashMap<K, V, S>> for HashMap<K, V, S> {
+    #[inline]
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        map.0
+    }
+}
+
 impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashableHashMap<K, V, S> {
     fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
         HashableHashMap(HashMap::from_iter(iter))
     }
 }
 
+impl<'a, K, V, S> FromIterator<(&'a K, &'a V)> for HashableHashMap<K, V, S>
+where K: Eq + Hash + Clone + 'a,
+      V: Clone + 'a,
+      S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item=(&'a K, &'a V)>>(iter: T) -> Self {
+        HashableHashMap(iter.into_iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+}
+
 impl<K: Hash, V: Hash, S> Hash for HashableHashMap<K, V, S> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        BUFFER.with(|buffer| {
-            // The cached buffer might already be in use farther up the call stack, so the
-            // algorithm reverts to a fallback as needed.
-            let fallback = RefCell::new(Vec::new());
-
-            let mut buffer = buffer.try_borrow_mut()
-                .unwrap_or_else(|_| fallback.borrow_mut());
-            buffer.clear();
-            buffer.extend(self.0.iter().map(|(k, v)| {
-                let mut inner_hasher = ahash::AHasher::default();
-                k.hash(&mut inner_hasher);
-                v.hash(&mut inner_hasher);
-                inner_hasher.finish()
-            }));
-            buffer.sort_unstable();
-            for hash in &*buffer {
-                state.write_u64(*hash);
-            }
-        });
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for HashableHashMap<K, V, S>
+where K: Eq + Hash + Borrow<Q>,
+      Q: Eq + Hash + ?Sized,
+      S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns a reference to the value for `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        &self.0[key]
     }
 }
 
@@ -287,26 +1242,137 @@ impl<'a, K, V, S> IntoIterator for &'a HashableHashMap<K, V, S> {
     }
 }
 
+impl<'a, K, V, S> IntoIterator for &'a mut HashableHashMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = std::collections::hash_map::IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::IterMut<'a, K, V> {
+        self.0.iter_mut()
+    }
+}
+
+impl<K, V, S> IntoIterator for HashableHashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = std::collections::hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::collections::hash_map::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+/// Orders maps by lexicographically comparing their entries in key order, which is how
+/// [`BTreeMap`] is ordered. The order is total and consistent with
+/// [`Eq`] but otherwise arbitrary.
+impl<K: Ord + Hash, V: Ord, S: BuildHasher> Ord for HashableHashMap<K, V, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut a: Vec<(&K, &V)> = self.0.iter().collect();
+        let mut b: Vec<(&K, &V)> = other.0.iter().collect();
+        a.sort_unstable_by_key(|&(k, _)| k);
+        b.sort_unstable_by_key(|&(k, _)| k);
+        a.cmp(&b)
+    }
+}
+
 impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> PartialEq for HashableHashMap<K, V, S> {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
     }
 }
 
+impl<K: Ord + Hash, V: Ord, S: BuildHasher> PartialOrd for HashableHashMap<K, V, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares maps that may use different hashers.
+fn maps_eq<K, V, S1, S2>(a: &HashMap<K, V, S1>, b: &HashMap<K, V, S2>) -> bool
+where K: Hash + Eq,
+      V: PartialEq,
+      S1: BuildHasher,
+      S2: BuildHasher,
+{
+    a.len() == b.len() && a.iter().all(|(k, v)| b.get(k) == Some(v))
+}
+
+impl<K, V, S, S2> PartialEq<HashMap<K, V, S2>> for HashableHashMap<K, V, S>
+where K: Hash + Eq,
+      V: PartialEq,
+      S: BuildHasher,
+      S2: BuildHasher,
+{
+    fn eq(&self, other: &HashMap<K, V, S2>) -> bool {
+        maps_eq(&self.0, other)
+    }
+}
+
+impl<K, V, S, S2> PartialEq<HashableHashMap<K, V, S>> for HashMap<K, V, S2>
+where K: Hash + Eq,
+      V: PartialEq,
+      S: BuildHasher,
+      S2: BuildHasher,
+{
+    fn eq(&self, other: &HashableHashMap<K, V, S>) -> bool {
+        maps_eq(self, &other.0)
+    }
+}
+
+/// Merges maps in iteration order, so later values win for duplicate keys.
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Sum for HashableHashMap<K, V, S> {
+    fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+        iter.fold(HashableHashMap::default(), |mut acc, map| {
+            acc.extend(map);
+            acc
+        })
+    }
+}
+
 impl<K, V, S> serde::Serialize for HashableHashMap<K, V, S>
 where K: Eq + Hash + serde::Serialize,
       V: serde::Serialize,
       S: BuildHasher,
 {
     fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = canonical::active() {
+            return canonical::serialize_map(encoding, self.0.iter(), ser);
+        }
         self.0.serialize(ser)
     }
 }
 
+impl<'de, K, V, S> serde::Deserialize<'de> for HashableHashMap<K, V, S>
+where K: Eq + Hash + serde::Deserialize<'de>,
+      V: serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashMap::deserialize(de).map(HashableHashMap)
+    }
+}
+
+impl<'de, K, V, S, E> serde::de::IntoDeserializer<'de, E> for HashableHashMap<K, V, S>
+where K: Eq + Hash + serde::de::IntoDeserializer<'de, E>,
+      V: serde::de::IntoDeserializer<'de, E>,
+      S: BuildHasher,
+      E: serde::de::Error,
+{
+    type Deserializer = <HashMap<K, V, S> as serde::de::IntoDeserializer<'de, E>>::Deserializer;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self.0.into_deserializer()
+    }
+}
+
 #[cfg(test)]
 mod hashable_hash_map_test {
     use crate::hash;
     use super::HashableHashMap;
+    use std::borrow::{Borrow, BorrowMut};
+    use std::collections::{BTreeMap, HashMap};
+    use std::ops::Index;
 
     #[test]
     fn different_hash_if_items_differ() {
@@ -363,4 +1429,226 @@ mod hashable_hash_map_test {
         }, "value");
         hash(&map); // No assertion as this test is just checking for a panic.
     }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut map = HashableHashMap::new();
+        map.insert("outer".to_string(), {
+            let mut map = HashableHashMap::new();
+            map.insert("inner".to_string(), 1);
+            map
+        });
+
+        let json = serde_json::to_string(&map).unwrap();
+        let round_tripped: HashableHashMap<String, HashableHashMap<String, i32>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(map, round_tripped);
+    }
+
+    #[test]
+    fn into_deserializer() {
+        use serde::de::{Deserialize, IntoDeserializer};
+        use serde::de::value::Error;
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        let mut map = HashableHashMap::new();
+        map.insert("x", 1);
+        map.insert("y", 2);
+        let point = Point::deserialize(IntoDeserializer::<Error>::into_deserializer(map)).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+    }
+
+    #[test]
+    fn flattened_catch_all() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            name: String,
+            #[serde(flatten)]
+            extra: HashableHashMap<String, u32>,
+        }
+
+        let config: Config = serde_json::from_str(r#"{"name":"n","a":1,"b":2}"#).unwrap();
+        assert_eq!(config.name, "n");
+        assert_eq!(config.extra.len(), 2);
+        assert_eq!(config.extra["b"], 2);
+    }
+
+    #[test]
+    fn into_iter_by_value() {
+        let map: HashableHashMap<String, u32> = vec![("a".to_string(), 1), ("b".to_string(), 2)].into_iter().collect();
+        let mut entries: Vec<(String, u32)> = map.into_iter().collect();
+        entries.sort();
+        assert_eq!(entries, [("a".to_string(), 1), ("b".to_string(), 2)]);
+    }
+
+    #[test]
+    fn extend_generically() {
+        fn add_all<T: Extend<(&'static str, u32)>>(target: &mut T) {
+            target.extend(vec![("a", 1), ("b", 2), ("a", 3)]);
+        }
+
+        let mut map = HashableHashMap::new();
+        add_all(&mut map);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 3);
+    }
+
+    #[test]
+    fn extend_from_refs() {
+        let source: HashableHashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let mut map: HashableHashMap<&str, u32> = HashableHashMap::new();
+        map.extend(source.iter().filter(|(_, &v)| v > 1));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["b"], 2);
+    }
+
+    #[test]
+    fn collect_from_refs() {
+        let source: HashableHashMap<String, Vec<u32>> =
+            vec![("a".to_string(), vec![1]), ("b".to_string(), vec![])].into_iter().collect();
+        let map: HashableHashMap<String, Vec<u32>> = source.iter().filter(|(_, v)| !v.is_empty()).collect();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["a"], [1]);
+    }
+
+    #[test]
+    fn index_generically() {
+        fn lookup<'a, M: Index<&'a str, Output = u32>>(map: &M) -> u32 {
+            map["a"]
+        }
+
+        let map: HashableHashMap<String, u32> = vec![("a".to_string(), 1)].into_iter().collect();
+        assert_eq!(lookup(&map), 1);
+    }
+
+    #[test]
+    fn borrow_as_std() {
+        fn len<M: Borrow<HashMap<String, u32, ahash::RandomState>>>(map: &M) -> usize {
+            map.borrow().len()
+        }
+
+        let mut map: HashableHashMap<String, u32> = HashableHashMap::new();
+        BorrowMut::<HashMap<_, _, _>>::borrow_mut(&mut map).insert("a".to_string(), 1);
+        assert_eq!(len(&map), 1);
+    }
+
+    #[test]
+    fn eq_std() {
+        let map: HashableHashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let std_map: HashMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(map, std_map);
+        assert_eq!(std_map, map);
+        assert_ne!(map, vec![("a", 1), ("b", 3)].into_iter().collect::<HashMap<_, _>>());
+    }
+
+    #[test]
+    fn sum_is_last_wins_merge() {
+        let maps = vec![vec![("a", 1), ("b", 1)], vec![("b", 2)]];
+        let merged: HashableHashMap<&str, u32> = maps.into_iter().map(|v| v.into_iter().collect::<HashableHashMap<_, _>>()).sum();
+        assert_eq!(merged, vec![("a", 1), ("b", 2)].into_iter().collect::<HashMap<_, _>>());
+    }
+
+    #[test]
+    fn ordered_by_sorted_entries() {
+        let a: HashableHashMap<&str, u32> = vec![("b", 1), ("a", 2)].into_iter().collect();
+        let b: HashableHashMap<&str, u32> = vec![("a", 2), ("c", 0)].into_iter().collect();
+        assert!(a < b);
+        assert_eq!(a.cmp(&a.clone()), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn display_is_sorted() {
+        let map: HashableHashMap<&str, u32> = vec![("b", 2), ("c", 3), ("a", 1)].into_iter().collect();
+        assert_eq!(map.to_string(), "{a: 1, b: 2, c: 3}");
+    }
+
+    #[test]
+    fn std_conversions() {
+        let std_map: HashMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        let map = HashableHashMap::from(std_map.clone());
+        assert_eq!(map, std_map);
+        assert_eq!(HashMap::from(map.clone()), std_map);
+        assert_eq!(map.into_inner(), std_map);
+    }
+
+    #[test]
+    fn from_array() {
+        let map = HashableHashMap::from([("a", 1), ("b", 2), ("a", 3)]);
+        assert_eq!(map, HashMap::from([("a", 3), ("b", 2)]));
+    }
+
+    #[test]
+    fn from_vec_and_boxed_slice() {
+        let map = HashableHashMap::from(vec![("a", 1), ("a", 2)]);
+        assert_eq!(map, HashMap::from([("a", 2)]));
+        assert_eq!(HashableHashMap::from(vec![("a", 2)].into_boxed_slice()), map);
+    }
+
+    #[test]
+    fn btree_conversions() {
+        let btree = BTreeMap::from([("b", 2), ("a", 1)]);
+        let map = HashableHashMap::from(btree.clone());
+        assert_eq!(map.to_btree_map(), btree);
+        assert_eq!(BTreeMap::from(map), btree);
+    }
+
+    #[test]
+    fn pop_entry() {
+        let mut map = HashableHashMap::from([("a", 1)]);
+        assert_eq!(map.pop_entry(), Some(("a", 1)));
+        assert_eq!(map.pop_entry(), None);
+    }
+
+    #[test]
+    fn get_many_mut() {
+        let mut map = HashableHashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let [a, c] = map.get_many_mut(["a", "c"]).unwrap();
+        std::mem::swap(a, c);
+        assert_eq!(map, HashableHashMap::from([("a", 3), ("b", 2), ("c", 1)]));
+
+        assert!(map.get_many_mut(["a", "a"]).is_none());
+        assert!(map.get_many_mut(["a", "z"]).is_none());
+
+        let mut units = HashableHashMap::from([(1, ()), (2, ())]);
+        assert!(units.get_many_mut([&1, &2]).is_some());
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn extract_if() {
+        let mut map = HashableHashMap::from([("a", 1), ("b", 2)]);
+        let extracted: Vec<(&str, u32)> = map.extract_if(|_, v| *v > 1).collect();
+        assert_eq!(extracted, [("b", 2)]);
+        assert_eq!(map, HashableHashMap::from([("a", 1)]));
+    }
+
+    #[test]
+    fn from_ref_and_from_mut() {
+        let mut std_map: HashMap<&str, u32, ahash::RandomState> = HashMap::default();
+        std_map.insert("a", 1);
+        let expected = hash(&HashableHashMap::from(std_map.clone()));
+        assert_eq!(hash(HashableHashMap::from_ref(&std_map)), expected);
+
+        HashableHashMap::from_mut(&mut std_map).insert("b", 2);
+        assert_eq!(std_map.len(), 2);
+    }
+
+    #[test]
+    fn into_iter_by_mut_ref() {
+        fn double<'a, I: IntoIterator<Item = (&'a &'static str, &'a mut u32)>>(entries: I) {
+            for (_, v) in entries {
+                *v *= 2;
+            }
+        }
+
+        let mut map: HashableHashMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        double(&mut map);
+        assert_eq!(map["a"], 2);
+        assert_eq!(map["b"], 4);
+    }
 }
@@ -0,0 +1,155 @@
+//! A hashable wrapper around [`lru`]'s bounded cache.
+//!
+//! [`HashableLruCache`] hashes and compares only the cached entries, like a
+//! [`crate::HashableHashMap`] holding them, so two caches with the same contents are equal even if
+//! their entries were used in a different order or they have different capacities. Equality and
+//! hashing use `peek`-style access and do not affect recency.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::lru::HashableLruCache;
+//! use std::num::NonZeroUsize;
+//!
+//! let mut a = HashableLruCache::new(NonZeroUsize::new(2).unwrap());
+//! a.put("x", 1);
+//! a.put("y", 2);
+//! let mut b = a.clone();
+//! b.get(&"x");
+//! assert_eq!(a, b);
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::lru::{DefaultHasher, LruCache};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::ops::{Deref, DerefMut};
+
+/// An [`LruCache`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`], ignoring
+/// recency order and capacity.
+#[repr(transparent)]
+pub struct HashableLruCache<K, V, S = DefaultHasher>(LruCache<K, V, S>);
+
+impl<K: Eq + Hash, V> HashableLruCache<K, V> {
+    /// Creates an empty cache that holds at most `cap` entries.
+    #[inline]
+    pub fn new(cap: NonZeroUsize) -> HashableLruCache<K, V> {
+        HashableLruCache(LruCache::new(cap))
+    }
+
+    /// Creates an empty cache with no limit on its size.
+    #[inline]
+    pub fn unbounded() -> HashableLruCache<K, V> {
+        HashableLruCache(LruCache::unbounded())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashableLruCache<K, V, S> {
+    #[inline]
+    pub fn with_hasher(cap: NonZeroUsize, hasher: S) -> Self {
+        HashableLruCache(LruCache::with_hasher(cap, hasher))
+    }
+}
+
+impl<K, V, S> HashableLruCache<K, V, S> {
+    /// Consumes the wrapper, returning the inner [`LruCache`].
+    #[inline]
+    pub fn into_inner(self) -> LruCache<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Clone for HashableLruCache<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        HashableLruCache(self.0.clone())
+    }
+}
+
+impl<K: Debug + Eq + Hash, V: Debug, S: BuildHasher> Debug for HashableLruCache<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K, V, S> Deref for HashableLruCache<K, V, S> {
+    type Target = LruCache<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableLruCache<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashableLruCache<K, V, S> {}
+
+impl<K, V, S> From<LruCache<K, V, S>> for HashableLruCache<K, V, S> {
+    #[inline]
+    fn from(cache: LruCache<K, V, S>) -> Self {
+        HashableLruCache(cache)
+    }
+}
+
+impl<K: Eq + Hash, V: Hash, S: BuildHasher> Hash for HashableLruCache<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> IntoIterator for &'a HashableLruCache<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = ::lru::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::lru::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for HashableLruCache<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().all(|(k, v)| other.0.peek(k) == Some(v))
+    }
+}
+
+#[cfg(test)]
+mod lru_test {
+    use super::HashableLruCache;
+    use crate::hash;
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn hash_ignores_recency_and_capacity() {
+        let mut a = HashableLruCache::new(NonZeroUsize::new(2).unwrap());
+        a.put("x", 1);
+        a.put("y", 2);
+        let mut b = HashableLruCache::unbounded();
+        b.put("y", 2);
+        b.put("x", 1);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("x", 1), ("y", 2)].into_iter().collect();
+        assert_eq!(hash(&a), hash(&std_map));
+    }
+
+    #[test]
+    fn eviction_changes_contents() {
+        let mut a = HashableLruCache::new(NonZeroUsize::new(2).unwrap());
+        a.put("x", 1);
+        a.put("y", 2);
+        let before = hash(&a);
+        a.get(&"x");
+        assert_eq!(hash(&a), before);
+        a.put("z", 3);
+        assert!(a.peek(&"y").is_none());
+        assert_ne!(hash(&a), before);
+    }
+}
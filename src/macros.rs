@@ -0,0 +1,53 @@
+//! Literal construction macros.
+
+/// Creates a [`HashableHashSet`](crate::HashableHashSet) containing the given elements.
+///
+/// # Example
+///
+/// ```rust
+/// let set = hashable::hashset!{"a", "b", "a"};
+/// assert_eq!(set.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! hashset {
+    ($($value:expr),* $(,)?) => {
+        $crate::HashableHashSet::from([$($value),*])
+    };
+}
+
+/// Creates a [`HashableHashMap`](crate::HashableHashMap) containing the given entries. Later
+/// entries win over earlier ones with the same key.
+///
+/// # Example
+///
+/// ```rust
+/// let map = hashable::hashmap!{"a" => 1, "b" => 2};
+/// assert_eq!(map["b"], 2);
+/// ```
+#[macro_export]
+macro_rules! hashmap {
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::HashableHashMap::from([$(($key, $value)),*])
+    };
+}
+
+#[cfg(test)]
+mod macros_test {
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn literals() {
+        let set: HashableHashSet<HashableHashSet<u32>> = hashset!{hashset!{1, 2}, hashset!{}};
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&hashset!{2, 1}));
+
+        let map: HashableHashMap<&str, u32> = hashmap!{
+            "a" => 1,
+            "b" => 2,
+            "a" => 3,
+        };
+        assert_eq!(map, HashableHashMap::from([("a", 3), ("b", 2)]));
+        let empty: HashableHashMap<u32, u32> = hashmap!{};
+        assert!(empty.is_empty());
+    }
+}
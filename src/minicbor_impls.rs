@@ -0,0 +1,70 @@
+//! [`minicbor`] implementations that share the encoding of the inner std collections.
+
+use crate::{HashableHashMap, HashableHashSet};
+use minicbor::decode::{self, Decode, Decoder};
+use minicbor::encode::{self, CborLen, Encode, Encoder, Write};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+impl<C, V: Encode<C>, S: BuildHasher> Encode<C> for HashableHashSet<V, S> {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>, ctx: &mut C) -> Result<(), encode::Error<W::Error>> {
+        self.0.encode(e, ctx)
+    }
+}
+
+impl<C, V: CborLen<C>, S: BuildHasher> CborLen<C> for HashableHashSet<V, S> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        self.0.cbor_len(ctx)
+    }
+}
+
+impl<'b, C, V, S> Decode<'b, C> for HashableHashSet<V, S>
+where V: Decode<'b, C> + Eq + Hash,
+      S: BuildHasher + Default,
+{
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        HashSet::decode(d, ctx).map(HashableHashSet)
+    }
+}
+
+impl<C, K, V, S> Encode<C> for HashableHashMap<K, V, S>
+where K: Encode<C> + Eq + Hash,
+      V: Encode<C>,
+      S: BuildHasher,
+{
+    fn encode<W: Write>(&self, e: &mut Encoder<W>, ctx: &mut C) -> Result<(), encode::Error<W::Error>> {
+        self.0.encode(e, ctx)
+    }
+}
+
+impl<C, K: CborLen<C>, V: CborLen<C>, S: BuildHasher> CborLen<C> for HashableHashMap<K, V, S> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        self.0.cbor_len(ctx)
+    }
+}
+
+impl<'b, C, K, V, S> Decode<'b, C> for HashableHashMap<K, V, S>
+where K: Decode<'b, C> + Eq + Hash,
+      V: Decode<'b, C>,
+      S: BuildHasher + Default,
+{
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        HashMap::decode(d, ctx).map(HashableHashMap)
+    }
+}
+
+#[cfg(test)]
+mod minicbor_impls_test {
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn round_trip() {
+        let mut map: HashableHashMap<String, HashableHashSet<u32>> = HashableHashMap::new();
+        map.insert("key".to_string(), vec![1, 2, 3].into_iter().collect());
+
+        let bytes = minicbor::to_vec(&map).unwrap();
+        assert_eq!(bytes.len(), minicbor::len(&map));
+        let decoded: HashableHashMap<String, HashableHashSet<u32>> = minicbor::decode(&bytes).unwrap();
+        assert_eq!(decoded, map);
+    }
+}
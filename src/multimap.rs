@@ -0,0 +1,198 @@
+//! A multimap built on [`HashableHashMap`] and [`HashableHashSet`].
+
+use crate::{HashableHashMap, HashableHashSet};
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+
+/// A map from each key to a set of values, such as an adjacency list. Keys without values are
+/// removed, so two multimaps are equal exactly when they contain the same key-value pairs.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableMultiMap;
+///
+/// let mut edges: HashableMultiMap<u32, u32> = HashableMultiMap::new();
+/// edges.insert(1, 2);
+/// edges.insert(1, 3);
+/// assert!(edges.contains(&1, &3));
+/// assert!(edges.remove(&1, &2));
+/// assert_eq!(edges.iter().collect::<Vec<_>>(), vec![(&1, &3)]);
+/// ```
+#[derive(Clone)]
+pub struct HashableMultiMap<K, V, S = ahash::RandomState> {
+    // Invariant: every set is nonempty.
+    map: HashableHashMap<K, HashableHashSet<V, S>, S>,
+}
+
+impl<K, V> HashableMultiMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableMultiMap<K, V> {
+        Default::default()
+    }
+}
+
+impl<K, V, S> HashableMultiMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableMultiMap { map: HashableHashMap::with_hasher(hasher) }
+    }
+
+    /// Returns the number of key-value pairs.
+    pub fn len(&self) -> usize {
+        self.map.values().map(|values| values.len()).sum()
+    }
+
+    /// Returns `true` if there are no key-value pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over the keys that have at least one value, in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item=&K> {
+        self.map.keys()
+    }
+
+    /// Iterates over every key-value pair, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item=(&K, &V)> {
+        self.map.iter().flat_map(|(k, values)| values.iter().map(move |v| (k, v)))
+    }
+
+    /// Returns the underlying map from key to values. Every set of values is nonempty.
+    #[inline]
+    pub fn as_map(&self) -> &HashableHashMap<K, HashableHashSet<V, S>, S> {
+        &self.map
+    }
+
+    /// Consumes the multimap, returning the map from key to values.
+    #[inline]
+    pub fn into_map(self) -> HashableHashMap<K, HashableHashSet<V, S>, S> {
+        self.map
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone> HashableMultiMap<K, V, S> {
+    /// Adds `value` to the values for `key`, returning whether it was newly added.
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        let hasher = self.map.hasher().clone();
+        self.map.entry(key).or_insert_with(|| HashableHashSet::with_hasher(hasher)).insert(value)
+    }
+
+    /// Removes `value` from the values for `key`, returning whether it was present.
+    pub fn remove<Q, R>(&mut self, key: &Q, value: &R) -> bool
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+          V: Borrow<R>,
+          R: Eq + Hash + ?Sized,
+    {
+        let values = match self.map.get_mut(key) {
+            Some(values) => values,
+            None => return false,
+        };
+        let removed = values.remove(value);
+        if values.is_empty() {
+            self.map.remove(key);
+        }
+        removed
+    }
+
+    /// Removes `key` and all of its values, returning the values.
+    pub fn remove_key<Q>(&mut self, key: &Q) -> Option<HashableHashSet<V, S>>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.map.remove(key)
+    }
+
+    /// Returns `true` if `value` is among the values for `key`.
+    pub fn contains<Q, R>(&self, key: &Q, value: &R) -> bool
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+          V: Borrow<R>,
+          R: Eq + Hash + ?Sized,
+    {
+        self.map.get(key).is_some_and(|values| values.contains(value))
+    }
+
+    /// Returns the values for `key`, or `None` if it has none.
+    pub fn get<Q>(&self, key: &Q) -> Option<&HashableHashSet<V, S>>
+    where K: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.map.get(key)
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for HashableMultiMap<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K, V, S: Default> Default for HashableMultiMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableMultiMap::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher> Eq for HashableMultiMap<K, V, S> {}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone> Extend<(K, V)> for HashableMultiMap<K, V, S> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher + Clone + Default> FromIterator<(K, V)> for HashableMultiMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        let mut multimap = HashableMultiMap::with_hasher(S::default());
+        multimap.extend(iter);
+        multimap
+    }
+}
+
+impl<K: Hash, V: Hash, S> Hash for HashableMultiMap<K, V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.map.hash(hasher);
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash, S: BuildHasher> PartialEq for HashableMultiMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+#[cfg(test)]
+mod multimap_test {
+    use crate::{hash, HashableHashSet, HashableMultiMap};
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut map: HashableMultiMap<&str, u32> = vec![("a", 1), ("a", 2), ("b", 3)].into_iter().collect();
+        assert_eq!(map.len(), 3);
+        assert!(!map.insert("a", 1));
+        assert!(map.contains("a", &2));
+        assert_eq!(map.get("a"), Some(&HashableHashSet::from([1, 2])));
+        assert!(map.remove("b", &3));
+        assert!(!map.remove("b", &3));
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec![&"a"]);
+        assert_eq!(map.remove_key("a"), Some(HashableHashSet::from([1, 2])));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn removing_the_last_value_removes_the_key() {
+        let mut a: HashableMultiMap<&str, u32> = vec![("a", 1), ("b", 2)].into_iter().collect();
+        let b: HashableMultiMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        a.remove("b", &2);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+    }
+}
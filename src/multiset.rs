@@ -0,0 +1,238 @@
+//! A multiset (bag) built on [`HashableHashMap`].
+
+use crate::HashableHashMap;
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Add, BitAnd, BitOr, Sub};
+
+/// A multiset that records how many times each element occurs, and which implements [`Hash`] by
+/// accounting for those multiplicities. Operators on references follow bag semantics: `|` takes
+/// the maximum count per element, `&` the minimum, `+` the sum, and `-` the difference, saturating
+/// at zero.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableMultiSet;
+///
+/// let a: HashableMultiSet<char> = "aab".chars().collect();
+/// let b: HashableMultiSet<char> = "abb".chars().collect();
+/// assert_eq!((&a | &b).count(&'b'), 2);
+/// assert_eq!((&a & &b).len(), 2);
+/// assert_eq!((&a + &b).len(), 6);
+/// assert_eq!((&a - &b), "a".chars().collect());
+/// ```
+#[derive(Clone)]
+pub struct HashableMultiSet<V, S = ahash::RandomState> {
+    // Invariant: every count is nonzero.
+    counts: HashableHashMap<V, usize, S>,
+}
+
+impl<V> HashableMultiSet<V> {
+    #[inline]
+    pub fn new() -> HashableMultiSet<V> {
+        Default::default()
+    }
+}
+
+impl<V, S> HashableMultiSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableMultiSet { counts: HashableHashMap::with_hasher(hasher) }
+    }
+
+    /// Returns the total number of elements, counting multiplicities.
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Returns the number of distinct elements.
+    #[inline]
+    pub fn distinct_len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns `true` if the multiset has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Iterates over the distinct elements and their counts, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item=(&V, usize)> {
+        self.counts.iter().map(|(v, &n)| (v, n))
+    }
+
+    /// Returns the underlying map from element to count. Every count is nonzero.
+    #[inline]
+    pub fn as_counts(&self) -> &HashableHashMap<V, usize, S> {
+        &self.counts
+    }
+
+    /// Consumes the multiset, returning the map from element to count.
+    #[inline]
+    pub fn into_counts(self) -> HashableHashMap<V, usize, S> {
+        self.counts
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> HashableMultiSet<V, S> {
+    /// Adds one occurrence of `value`, returning its new count.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> usize {
+        self.insert_n(value, 1)
+    }
+
+    /// Adds `n` occurrences of `value`, returning its new count.
+    pub fn insert_n(&mut self, value: V, n: usize) -> usize {
+        if n == 0 {
+            return self.counts.get(&value).copied().unwrap_or(0);
+        }
+        self.counts.add(value, n)
+    }
+
+    /// Removes one occurrence of `value`, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.counts.decrement_and_remove_at_zero(value).is_some()
+    }
+
+    /// Returns the number of occurrences of `value`.
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if every element occurs in `other` at least as many times as here.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.counts.iter().all(|(v, &n)| other.count(v) >= n)
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> HashableMultiSet<V, S> {
+    /// Combines the counts of both multisets with `op`, dropping elements whose result is zero.
+    fn combine<F: Fn(usize, usize) -> usize>(&self, other: &Self, op: F) -> Self {
+        let mut result = HashableMultiSet::with_hasher(self.counts.hasher().clone());
+        let keys = self.counts.keys().chain(other.counts.keys().filter(|v| !self.counts.contains_key(*v)));
+        for v in keys {
+            let n = op(self.count(v), other.count(v));
+            if n > 0 {
+                result.counts.insert(v.clone(), n);
+            }
+        }
+        result
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> Add<&HashableMultiSet<V, S>> for &HashableMultiSet<V, S> {
+    type Output = HashableMultiSet<V, S>;
+
+    /// Returns the sum, in which counts are added.
+    fn add(self, rhs: &HashableMultiSet<V, S>) -> HashableMultiSet<V, S> {
+        self.combine(rhs, |a, b| a + b)
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> BitAnd<&HashableMultiSet<V, S>> for &HashableMultiSet<V, S> {
+    type Output = HashableMultiSet<V, S>;
+
+    /// Returns the intersection, which keeps the smaller count of each element.
+    fn bitand(self, rhs: &HashableMultiSet<V, S>) -> HashableMultiSet<V, S> {
+        self.combine(rhs, usize::min)
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> BitOr<&HashableMultiSet<V, S>> for &HashableMultiSet<V, S> {
+    type Output = HashableMultiSet<V, S>;
+
+    /// Returns the union, which keeps the larger count of each element.
+    fn bitor(self, rhs: &HashableMultiSet<V, S>) -> HashableMultiSet<V, S> {
+        self.combine(rhs, usize::max)
+    }
+}
+
+impl<V: Debug, S> Debug for HashableMultiSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.counts.fmt(f)
+    }
+}
+
+impl<V, S: Default> Default for HashableMultiSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableMultiSet::with_hasher(S::default())
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Eq for HashableMultiSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for HashableMultiSet<V, S> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for v in iter {
+            self.insert(v);
+        }
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for HashableMultiSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        let mut multiset = HashableMultiSet::with_hasher(S::default());
+        multiset.extend(iter);
+        multiset
+    }
+}
+
+impl<V: Hash, S> Hash for HashableMultiSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.counts.hash(hasher);
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialEq for HashableMultiSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<V: Eq + Hash + Clone, S: BuildHasher + Clone> Sub<&HashableMultiSet<V, S>> for &HashableMultiSet<V, S> {
+    type Output = HashableMultiSet<V, S>;
+
+    /// Returns the difference, in which counts are subtracted and saturate at zero.
+    fn sub(self, rhs: &HashableMultiSet<V, S>) -> HashableMultiSet<V, S> {
+        self.combine(rhs, usize::saturating_sub)
+    }
+}
+
+#[cfg(test)]
+mod multiset_test {
+    use crate::{hash, HashableMultiSet};
+
+    #[test]
+    fn counts_and_removal() {
+        let mut bag: HashableMultiSet<&str> = vec!["a", "a", "b"].into_iter().collect();
+        assert_eq!(bag.len(), 3);
+        assert_eq!(bag.distinct_len(), 2);
+        assert_eq!(bag.count("a"), 2);
+        assert!(bag.remove("b"));
+        assert!(!bag.remove("b"));
+        assert_eq!(bag.insert_n("c", 0), 0);
+        assert_eq!(bag, vec!["a", "a"].into_iter().collect());
+    }
+
+    #[test]
+    fn hash_accounts_for_multiplicity() {
+        let once: HashableMultiSet<u32> = vec![1, 2].into_iter().collect();
+        let twice: HashableMultiSet<u32> = vec![1, 1, 2].into_iter().collect();
+        assert_ne!(hash(&once), hash(&twice));
+        assert_eq!(hash(&twice), hash(&vec![2, 1, 1].into_iter().collect::<HashableMultiSet<u32>>()));
+        assert!(once.is_subset(&twice));
+        assert!(!twice.is_subset(&once));
+        assert_eq!(&twice - &twice, HashableMultiSet::new());
+    }
+}
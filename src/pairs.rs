@@ -0,0 +1,76 @@
+//! A serde `with` module that represents a map as a sequence of `[key, value]` pairs.
+//!
+//! Formats such as JSON only allow string keys, so a map like `HashableHashMap<(u32, u32), V>`
+//! cannot be serialized as a JSON object. This module emits `[[key, value], ...]` instead and
+//! reads the same representation back. It works with [`HashableHashMap`] as well as any std map.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::HashableHashMap;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Grid {
+//!     #[serde(with = "hashable::pairs")]
+//!     cells: HashableHashMap<(u32, u32), char>,
+//! }
+//!
+//! let grid = Grid { cells: vec![((0, 1), 'x')].into_iter().collect() };
+//! assert_eq!(serde_json::to_string(&grid).unwrap(), r#"{"cells":[[[0,1],"x"]]}"#);
+//! ```
+//!
+//! [`HashableHashMap`]: crate::HashableHashMap
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::iter::FromIterator;
+
+/// Serializes a map as a sequence of `[key, value]` pairs.
+pub fn serialize<'a, T, K, V, Ser>(map: &'a T, ser: Ser) -> Result<Ser::Ok, Ser::Error>
+where &'a T: IntoIterator<Item = (&'a K, &'a V)>,
+      K: Serialize + 'a,
+      V: Serialize + 'a,
+      Ser: Serializer,
+{
+    ser.collect_seq(map)
+}
+
+/// Deserializes a map from a sequence of `[key, value]` pairs. Later pairs win over earlier ones
+/// with the same key.
+pub fn deserialize<'de, T, K, V, D>(de: D) -> Result<T, D::Error>
+where T: FromIterator<(K, V)> + IntoIterator<Item = (K, V)>,
+      K: Deserialize<'de>,
+      V: Deserialize<'de>,
+      D: Deserializer<'de>,
+{
+    Vec::<(K, V)>::deserialize(de).map(|pairs| pairs.into_iter().collect())
+}
+
+#[cfg(test)]
+mod pairs_test {
+    use crate::HashableHashMap;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Wrapper {
+        #[serde(with = "crate::pairs")]
+        map: HashableHashMap<(i32, i32), String>,
+    }
+
+    #[test]
+    fn round_trip_non_string_keys() {
+        let mut map = HashableHashMap::new();
+        map.insert((1, 2), "a".to_string());
+        map.insert((3, 4), "b".to_string());
+        let wrapper = Wrapper { map };
+
+        let json = serde_json::to_string(&wrapper).unwrap();
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), wrapper);
+    }
+
+    #[test]
+    fn only_pairs_supports_non_string_keys_in_json() {
+        let mut map = HashableHashMap::new();
+        map.insert((1, 2), "a".to_string());
+        assert!(serde_json::to_string(&map).is_err());
+        assert!(serde_json::to_string(&Wrapper { map }).is_ok());
+    }
+}
@@ -0,0 +1,366 @@
+//! A frozen set backed by a minimal perfect hash function.
+
+use crate::sketch::mix;
+use crate::{prehash, HashableHashSet};
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+
+/// The average number of elements per bucket of displacements.
+const LAMBDA: usize = 5;
+
+/// The number of seeds tried before falling back to [`Index::Sorted`].
+const MAX_SEEDS: u64 = 16;
+
+/// The number of displacements tried for a bucket before giving up on the seed.
+const MAX_DISPLACEMENT_ATTEMPTS: u64 = 1 << 16;
+
+/// The pair of displacements that places the elements of one bucket.
+type Displacement = (u32, u32);
+
+/// An immutable set that places its elements with a minimal perfect hash function, built with the
+/// hash-and-displace algorithm when the set is created. Each lookup hashes the query once and
+/// compares it with a single element, and the only overhead beyond the elements themselves is a
+/// pair of `u32` displacements per five elements.
+///
+/// No perfect hash function exists when distinct elements share a pre-hash, and the search for one
+/// gives up after a fixed number of attempts. In either case the set instead stores the elements
+/// sorted by pre-hash, along with the pre-hashes, and lookups binary search them.
+///
+/// Like [`crate::FrozenHashableHashSet`], the set stores its hash, which equals that of a frozen
+/// set with the same elements, so [`Hash`] takes constant time and [`Eq`] compares the stored
+/// hashes before comparing elements.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableHashSet;
+///
+/// let set = HashableHashSet::from(["red", "green", "blue"]).freeze_perfect();
+/// assert!(set.contains("green"));
+/// assert!(!set.contains("yellow"));
+/// let frozen = HashableHashSet::from(["blue", "green", "red"]).freeze();
+/// assert_eq!(set.precomputed_hash(), frozen.precomputed_hash());
+/// ```
+#[derive(Clone)]
+pub struct PerfectHashableHashSet<V> {
+    // Each element is where `index` expects it.
+    elements: Vec<V>,
+    index: Index,
+    hash: u64,
+}
+
+/// How [`PerfectHashableHashSet`] locates an element.
+#[derive(Clone)]
+enum Index {
+    /// Elements are placed by a perfect hash function.
+    Perfect { displacements: Vec<Displacement>, seed: u64 },
+    /// Elements are sorted by pre-hash, and `prehashes[i]` is the pre-hash of element `i`.
+    Sorted { prehashes: Vec<u64> },
+}
+
+/// The hashes derived from an element's pre-hash for a given seed.
+struct Hashes {
+    bucket: u32,
+    f1: u32,
+    f2: u32,
+}
+
+impl Hashes {
+    fn new(prehash: u64, seed: u64) -> Hashes {
+        let a = mix(prehash, seed);
+        let b = mix(a, 1);
+        Hashes { bucket: (a >> 32) as u32, f1: a as u32, f2: b as u32 }
+    }
+
+    fn index(&self, (d1, d2): Displacement, len: usize) -> usize {
+        let displaced = d2.wrapping_add(self.f1.wrapping_mul(d1)).wrapping_add(self.f2);
+        displaced as usize % len
+    }
+}
+
+impl<V> PerfectHashableHashSet<V> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Iterates over the elements in an arbitrary order that is fixed at construction.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, V> {
+        self.elements.iter()
+    }
+
+    /// Returns the hash computed at construction.
+    #[inline]
+    pub fn precomputed_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Consumes the set, returning its elements in iteration order.
+    #[inline]
+    pub fn into_vec(self) -> Vec<V> {
+        self.elements
+    }
+
+    /// Returns the element equal to `value`, if any.
+    pub fn get<Q>(&self, value: &Q) -> Option<&V>
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        if self.elements.is_empty() {
+            return None;
+        }
+        match &self.index {
+            Index::Perfect { displacements, seed } => {
+                let hashes = Hashes::new(prehash(value), *seed);
+                let displacement = displacements[hashes.bucket as usize % displacements.len()];
+                let element = &self.elements[hashes.index(displacement, self.elements.len())];
+                if element.borrow() == value { Some(element) } else { None }
+            }
+            Index::Sorted { prehashes } => {
+                let hash = prehash(value);
+                let start = prehashes.partition_point(|&h| h < hash);
+                prehashes[start..].iter()
+                    .take_while(|&&h| h == hash)
+                    .zip(&self.elements[start..])
+                    .map(|(_, element)| element)
+                    .find(|element| (*element).borrow() == value)
+            }
+        }
+    }
+
+    /// Returns `true` if the set contains `value`.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.get(value).is_some()
+    }
+}
+
+impl<V: Hash> PerfectHashableHashSet<V> {
+    /// Builds the perfect hash function for distinct `elements`, or the sorted fallback if there
+    /// is none.
+    fn build(elements: Vec<V>, hash: u64) -> PerfectHashableHashSet<V> {
+        let prehashes: Vec<u64> = elements.iter().map(prehash).collect();
+        let mut sorted = prehashes.clone();
+        sorted.sort_unstable();
+        let distinct = sorted.windows(2).all(|w| w[0] != w[1]);
+        let found = if distinct {
+            (0..MAX_SEEDS).find_map(|seed| try_displace(&prehashes, seed).map(|(d, p)| (seed, d, p)))
+        } else {
+            None
+        };
+
+        let (index, placement) = match found {
+            Some((seed, displacements, placement)) => (Index::Perfect { displacements, seed }, placement),
+            None => {
+                let mut order: Vec<usize> = (0..elements.len()).collect();
+                order.sort_unstable_by_key(|&i| prehashes[i]);
+                let mut placement = vec![0; elements.len()];
+                for (slot, &i) in order.iter().enumerate() {
+                    placement[i] = slot;
+                }
+                (Index::Sorted { prehashes: sorted }, placement)
+            }
+        };
+        let mut slots: Vec<Option<V>> = elements.iter().map(|_| None).collect();
+        for (element, index) in elements.into_iter().zip(placement) {
+            slots[index] = Some(element);
+        }
+        let elements = slots.into_iter().map(Option::unwrap).collect();
+        PerfectHashableHashSet { elements, index, hash }
+    }
+}
+
+/// Searches for a displacement per bucket that places every element in its own slot, returning
+/// the displacements and each element's slot, or `None` if some bucket cannot be placed.
+///
+/// Each bucket with several elements tries at most [`MAX_DISPLACEMENT_ATTEMPTS`] displacements, so
+/// a seed that does not work is abandoned after linear work rather than a scan of every pair.
+/// Buckets with a single element, which come last, go straight into the remaining free slots.
+fn try_displace(prehashes: &[u64], seed: u64) -> Option<(Vec<Displacement>, Vec<usize>)> {
+    let len = prehashes.len();
+    let hashes: Vec<Hashes> = prehashes.iter().map(|&h| Hashes::new(h, seed)).collect();
+    let buckets_len = len.div_ceil(LAMBDA).max(1);
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); buckets_len];
+    for (i, hashes) in hashes.iter().enumerate() {
+        buckets[hashes.bucket as usize % buckets_len].push(i);
+    }
+
+    // Place the largest buckets first, while the most slots are free.
+    let mut order: Vec<usize> = (0..buckets_len).collect();
+    order.sort_by_key(|&b| std::cmp::Reverse(buckets[b].len()));
+
+    let mut displacements = vec![(0, 0); buckets_len];
+    let mut placement = vec![0; len];
+    // The free slots in any order, and where each free slot is within `free`.
+    let mut free: Vec<usize> = (0..len).collect();
+    let mut free_at: Vec<usize> = (0..len).collect();
+    // Slots claimed by the candidate displacement, to detect collisions within a bucket.
+    let mut claimed: Vec<usize> = Vec::new();
+    let mut order = order.into_iter().take_while(|&b| !buckets[b].is_empty()).peekable();
+    while let Some(b) = order.next_if(|&b| buckets[b].len() > 1) {
+        // Each candidate puts the bucket's first element in a free slot, so only the others can
+        // collide.
+        let first = &hashes[buckets[b][0]];
+        let mut candidates = (0..MAX_DISPLACEMENT_ATTEMPTS).map(|attempt| {
+            let random = mix(attempt, seed);
+            let (d1, slot) = (random as u32, free[(random >> 32) as usize % free.len()]);
+            (d1, (slot as u32).wrapping_sub(first.f1.wrapping_mul(d1)).wrapping_sub(first.f2))
+        });
+        let found = candidates.find(|&d| {
+            claimed.clear();
+            for &i in &buckets[b] {
+                let index = hashes[i].index(d, len);
+                if free_at[index] == usize::MAX || claimed.contains(&index) {
+                    return false;
+                }
+                claimed.push(index);
+            }
+            true
+        })?;
+        displacements[b] = found;
+        for (&i, &index) in buckets[b].iter().zip(&claimed) {
+            placement[i] = index;
+            let at = std::mem::replace(&mut free_at[index], usize::MAX);
+            free.swap_remove(at);
+            if let Some(&moved) = free.get(at) {
+                free_at[moved] = at;
+            }
+        }
+    }
+    // With no multiplier, the second displacement alone picks the slot.
+    for (b, slot) in order.zip(free) {
+        let i = buckets[b][0];
+        displacements[b] = (0, (slot as u32).wrapping_sub(hashes[i].f2));
+        placement[i] = slot;
+    }
+    Some((displacements, placement))
+}
+
+impl<V: Hash, S> HashableHashSet<V, S> {
+    /// Makes the set immutable, building a minimal perfect hash function over its elements.
+    pub fn freeze_perfect(self) -> PerfectHashableHashSet<V> {
+        let hash = prehash(&self);
+        PerfectHashableHashSet::build(self.0.into_iter().collect(), hash)
+    }
+}
+
+impl<V: Debug> Debug for PerfectHashableHashSet<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.elements.iter()).finish()
+    }
+}
+
+impl<'de, V: Eq + Hash + serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectHashableHashSet<V> {
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashSet::<V>::deserialize(de).map(HashableHashSet::freeze_perfect)
+    }
+}
+
+impl<V: Eq + Hash> Eq for PerfectHashableHashSet<V> {}
+
+impl<V: Hash, S: BuildHasher> From<HashableHashSet<V, S>> for PerfectHashableHashSet<V> {
+    #[inline]
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.freeze_perfect()
+    }
+}
+
+impl<V: Eq + Hash> FromIterator<V> for PerfectHashableHashSet<V> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        iter.into_iter().collect::<HashableHashSet<V>>().freeze_perfect()
+    }
+}
+
+impl<V> Hash for PerfectHashableHashSet<V> {
+    #[inline]
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.hash);
+    }
+}
+
+impl<'a, V> IntoIterator for &'a PerfectHashableHashSet<V> {
+    type Item = &'a V;
+    type IntoIter = std::slice::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::slice::Iter<'a, V> {
+        self.elements.iter()
+    }
+}
+
+impl<V: Eq + Hash> PartialEq for PerfectHashableHashSet<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.len() == other.len() && self.iter().all(|v| other.contains(v))
+    }
+}
+
+impl<V: serde::Serialize> serde::Serialize for PerfectHashableHashSet<V> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = crate::canonical::active() {
+            return crate::canonical::serialize_seq(encoding, self.elements.iter(), ser);
+        }
+        ser.collect_seq(self.elements.iter())
+    }
+}
+
+#[cfg(test)]
+mod perfect_test {
+    use crate::{hash, HashableHashSet, PerfectHashableHashSet};
+
+    #[test]
+    fn finds_every_element() {
+        for len in [0, 1, 2, 7, 100, 5000] {
+            let set: PerfectHashableHashSet<u64> = (0..len).map(|i| i * 3).collect();
+            assert_eq!(set.len(), len as usize);
+            assert!((0..len).all(|i| set.contains(&(i * 3))));
+            assert!(!(0..len).any(|i| set.contains(&(i * 3 + 1))));
+        }
+    }
+
+    #[test]
+    fn large_sets_get_a_perfect_index() {
+        // Unplaceable buckets give up after a bounded search, so this stays fast.
+        let set: PerfectHashableHashSet<u64> = (0..200_000).collect();
+        assert!(matches!(set.index, super::Index::Perfect { .. }));
+        assert!((0..200_000).all(|i| set.contains(&i)));
+    }
+
+    #[test]
+    fn hash_matches_frozen_set() {
+        let set = HashableHashSet::from(["a".to_string(), "b".to_string()]);
+        let perfect = set.clone().freeze_perfect();
+        assert!(perfect.contains("a"));
+        assert_eq!(hash(&perfect), hash(&set.freeze()));
+        assert_eq!(perfect, vec!["b".to_string(), "a".to_string()].into_iter().collect());
+    }
+
+    #[test]
+    fn falls_back_when_prehashes_collide() {
+        use std::hash::{Hash, Hasher};
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Colliding(u32);
+
+        impl Hash for Colliding {
+            fn hash<H: Hasher>(&self, _: &mut H) {}
+        }
+
+        let set: HashableHashSet<Colliding> = (0..10).map(Colliding).collect();
+        let perfect: PerfectHashableHashSet<Colliding> = (0..10).map(Colliding).collect();
+        assert!((0..10).all(|i| perfect.contains(&Colliding(i))));
+        assert!(!perfect.contains(&Colliding(10)));
+        assert_eq!(hash(&perfect), hash(&set.freeze()));
+    }
+}
@@ -0,0 +1,170 @@
+//! One-call persistence with an integrity check.
+//!
+//! [`save_to`](HashableHashSet::save_to) writes the collection's order-independent hash followed by
+//! its bincode encoding, and [`load_from`](HashableHashSet::load_from) recomputes the hash after
+//! decoding and rejects the data if the two differ. The hash uses a fixed function rather than the
+//! per-process one behind [`Hash`], so a file can be loaded by any process: 64-bit FNV-1a over
+//! the collection as hashed by [`Hash`], with entries pre-hashed the same way and integers written
+//! in little-endian order, followed by the SplitMix64 finalizer.
+//!
+//! [`Hash`]: std::hash::Hash
+
+use crate::stable::stable_prehash;
+use crate::{HashableHashMap, HashableHashSet};
+use bincode::de::Decode;
+use bincode::enc::Encode;
+use bincode::error::{DecodeError, EncodeError};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
+
+/// An error from [`HashableHashSet::load_from`]/[`HashableHashMap::load_from`] or the
+/// corresponding `save_to` methods.
+#[derive(Debug)]
+pub enum PersistError {
+    /// The collection could not be encoded or written.
+    Encode(EncodeError),
+    /// The data could not be read or decoded.
+    Decode(DecodeError),
+    /// The data decoded successfully but does not hash to the stored checksum.
+    ChecksumMismatch {
+        /// The checksum stored alongside the data.
+        expected: u64,
+        /// The checksum of the decoded collection.
+        actual: u64,
+    },
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PersistError::Encode(e) => write!(f, "failed to encode collection: {}", e),
+            PersistError::Decode(e) => write!(f, "failed to decode collection: {}", e),
+            PersistError::ChecksumMismatch { expected, actual } => write!(
+                f, "checksum mismatch: expected {:#018x}, found {:#018x}", expected, actual),
+        }
+    }
+}
+
+impl Error for PersistError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistError::Encode(e) => Some(e),
+            PersistError::Decode(e) => Some(e),
+            PersistError::ChecksumMismatch { .. } => None,
+        }
+    }
+}
+
+impl From<EncodeError> for PersistError {
+    fn from(e: EncodeError) -> Self {
+        PersistError::Encode(e)
+    }
+}
+
+impl From<DecodeError> for PersistError {
+    fn from(e: DecodeError) -> Self {
+        PersistError::Decode(e)
+    }
+}
+
+fn save<T: Encode + Hash, W: Write>(value: &T, mut writer: W) -> Result<(), PersistError> {
+    bincode::encode_into_std_write((stable_prehash(value), value), &mut writer, bincode::config::standard())?;
+    Ok(())
+}
+
+fn load<T: Decode<()> + Hash, R: Read>(mut reader: R) -> Result<T, PersistError> {
+    let (expected, value): (u64, T) = bincode::decode_from_std_read(&mut reader, bincode::config::standard())?;
+    let actual = stable_prehash(&value);
+    if actual != expected {
+        return Err(PersistError::ChecksumMismatch { expected, actual });
+    }
+    Ok(value)
+}
+
+impl<V, S> HashableHashSet<V, S>
+where V: Eq + Hash,
+      S: BuildHasher,
+{
+    /// Writes the set and its checksum to `writer`.
+    pub fn save_to<W: Write>(&self, writer: W) -> Result<(), PersistError>
+    where V: Encode,
+    {
+        save(self, writer)
+    }
+
+    /// Reads a set written by [`save_to`](Self::save_to), verifying its checksum.
+    pub fn load_from<R: Read>(reader: R) -> Result<Self, PersistError>
+    where V: Decode<()>,
+          S: Default,
+    {
+        load(reader)
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S>
+where K: Eq + Hash,
+      V: Hash,
+      S: BuildHasher,
+{
+    /// Writes the map and its checksum to `writer`.
+    pub fn save_to<W: Write>(&self, writer: W) -> Result<(), PersistError>
+    where K: Encode,
+          V: Encode,
+    {
+        save(self, writer)
+    }
+
+    /// Reads a map written by [`save_to`](Self::save_to), verifying its checksum.
+    pub fn load_from<R: Read>(reader: R) -> Result<Self, PersistError>
+    where K: Decode<()>,
+          V: Decode<()>,
+          S: Default,
+    {
+        load(reader)
+    }
+}
+
+#[cfg(test)]
+mod persist_test {
+    use super::PersistError;
+    use crate::{HashableHashMap, HashableHashSet};
+
+    fn map() -> HashableHashMap<String, HashableHashSet<u32>> {
+        let mut map = HashableHashMap::new();
+        map.insert("a".to_string(), vec![1, 2, 3].into_iter().collect());
+        map.insert("b".to_string(), vec![4].into_iter().collect());
+        map
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bytes = Vec::new();
+        map().save_to(&mut bytes).unwrap();
+        assert_eq!(HashableHashMap::load_from(bytes.as_slice()).unwrap(), map());
+    }
+
+    #[test]
+    fn checksum_is_fixed() {
+        // A checksum that depended on the process would make files unreadable in later processes.
+        let mut bytes = Vec::new();
+        map().save_to(&mut bytes).unwrap();
+        let (checksum, _): (u64, usize) = bincode::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+        assert_eq!(checksum, 11291926962641628046);
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let mut bytes = Vec::new();
+        map().save_to(&mut bytes).unwrap();
+        // The last byte is an element of one of the sets, so the data still decodes.
+        *bytes.last_mut().unwrap() ^= 0x01;
+        let result = HashableHashMap::<String, HashableHashSet<u32>>::load_from(bytes.as_slice());
+        assert!(matches!(result, Err(PersistError::ChecksumMismatch { .. })));
+
+        assert!(matches!(
+            HashableHashSet::<u32>::load_from(&bytes[..4]),
+            Err(PersistError::Decode(_))));
+    }
+}
@@ -0,0 +1,216 @@
+//! A set that caches the pre-hash of each element.
+
+use crate::{hash_prehashes, prehash, HashableHashSet};
+use std::borrow::Borrow;
+use std::collections::{hash_map, HashMap};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+
+/// A set that stores each element's pre-hash alongside it, computed on insertion. Hashing the set
+/// then only sorts the cached values instead of re-hashing every element, which pays off when the
+/// elements are expensive to hash and the set is hashed repeatedly.
+///
+/// The hash equals that of a [`HashableHashSet`] with the same elements.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::PrehashedHashableHashSet;
+///
+/// let mut set: PrehashedHashableHashSet<String> = PrehashedHashableHashSet::new();
+/// assert!(set.insert("a".to_string()));
+/// assert!(set.contains("a"));
+/// assert!(set.remove("a"));
+/// assert!(set.is_empty());
+/// ```
+#[derive(Clone)]
+pub struct PrehashedHashableHashSet<V, S = ahash::RandomState> {
+    // Maps each element to its pre-hash.
+    elements: HashMap<V, u64, S>,
+}
+
+impl<V> PrehashedHashableHashSet<V> {
+    #[inline]
+    pub fn new() -> PrehashedHashableHashSet<V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> PrehashedHashableHashSet<V> {
+        PrehashedHashableHashSet::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<V, S> PrehashedHashableHashSet<V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        PrehashedHashableHashSet { elements: HashMap::with_hasher(hasher) }
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        PrehashedHashableHashSet { elements: HashMap::with_capacity_and_hasher(capacity, hasher) }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Iterates over the elements in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> hash_map::Keys<'_, V, u64> {
+        self.elements.keys()
+    }
+
+    pub fn clear(&mut self) {
+        self.elements.clear();
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PrehashedHashableHashSet<V, S> {
+    /// Adds a value, returning whether it was newly added.
+    pub fn insert(&mut self, value: V) -> bool {
+        match self.elements.entry(value) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(entry) => {
+                let hash = prehash(entry.key());
+                entry.insert(hash);
+                true
+            }
+        }
+    }
+
+    /// Removes a value, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.elements.remove(value).is_some()
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        self.elements.contains_key(value)
+    }
+
+    /// Converts into a [`HashableHashSet`], discarding the cached pre-hashes.
+    pub fn into_hashable_hash_set(self) -> HashableHashSet<V, S>
+    where S: Default,
+    {
+        self.elements.into_keys().collect()
+    }
+}
+
+impl<V: Debug, S> Debug for PrehashedHashableHashSet<V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.elements.keys()).finish()
+    }
+}
+
+impl<V, S: Default> Default for PrehashedHashableHashSet<V, S> {
+    #[inline]
+    fn default() -> Self {
+        PrehashedHashableHashSet { elements: HashMap::default() }
+    }
+}
+
+impl<'de, V, S> serde::Deserialize<'de> for PrehashedHashableHashSet<V, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        HashableHashSet::deserialize(de).map(PrehashedHashableHashSet::from)
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> Eq for PrehashedHashableHashSet<V, S> {}
+
+impl<V: Eq + Hash, S: BuildHasher> Extend<V> for PrehashedHashableHashSet<V, S> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> From<HashableHashSet<V, S>> for PrehashedHashableHashSet<V, S> {
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher + Default> FromIterator<V> for PrehashedHashableHashSet<V, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        let mut set = PrehashedHashableHashSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<V, S> Hash for PrehashedHashableHashSet<V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.elements.values().copied(), hasher);
+    }
+}
+
+impl<'a, V, S> IntoIterator for &'a PrehashedHashableHashSet<V, S> {
+    type Item = &'a V;
+    type IntoIter = hash_map::Keys<'a, V, u64>;
+
+    #[inline]
+    fn into_iter(self) -> hash_map::Keys<'a, V, u64> {
+        self.elements.keys()
+    }
+}
+
+impl<V, S> IntoIterator for PrehashedHashableHashSet<V, S> {
+    type Item = V;
+    type IntoIter = hash_map::IntoKeys<V, u64>;
+
+    #[inline]
+    fn into_iter(self) -> hash_map::IntoKeys<V, u64> {
+        self.elements.into_keys()
+    }
+}
+
+impl<V: Eq + Hash, S: BuildHasher> PartialEq for PrehashedHashableHashSet<V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.elements.keys().all(|v| other.elements.contains_key(v))
+    }
+}
+
+impl<V: serde::Serialize, S> serde::Serialize for PrehashedHashableHashSet<V, S> {
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = crate::canonical::active() {
+            return crate::canonical::serialize_seq(encoding, self.elements.keys(), ser);
+        }
+        ser.collect_seq(self.elements.keys())
+    }
+}
+
+#[cfg(test)]
+mod prehashed_test {
+    use crate::{hash, HashableHashSet, PrehashedHashableHashSet};
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let mut set: PrehashedHashableHashSet<&str> = vec!["a", "b", "c"].into_iter().collect();
+        assert_eq!(hash(&set), hash(&HashableHashSet::from(["c", "b", "a"])));
+
+        set.remove("b");
+        set.insert("d");
+        assert_eq!(hash(&set), hash(&HashableHashSet::from(["a", "c", "d"])));
+        assert_eq!(set.into_hashable_hash_set(), HashableHashSet::from(["a", "c", "d"]));
+    }
+}
@@ -0,0 +1,140 @@
+//! Compact digests of a collection's contents for comparing remote replicas.
+//!
+//! A [`Prehashes`] value holds a sorted fixed hash of each element. Replicas can exchange these
+//! instead of their full contents to detect equality, then use [`Prehashes::difference`] and
+//! `select_prehashes` to ship only the elements that differ. Unlike the per-process pre-hashes
+//! behind the collections' [`Hash`] implementations, these are the same in every process and on
+//! every platform, as long as the elements' [`Hash`] implementations do not change. Distinct
+//! elements may rarely collide.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::HashableHashSet;
+//!
+//! let local: HashableHashSet<_> = vec![1, 2, 3].into_iter().collect();
+//! let remote: HashableHashSet<_> = vec![2, 3, 4].into_iter().collect();
+//! let missing_remotely = local.prehashes().difference(&remote.prehashes());
+//! assert_eq!(local.select_prehashes(&missing_remotely), vec![&1]);
+//! ```
+
+use crate::stable::stable_prehash;
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// The sorted pre-hashes of a collection's elements (or, for maps, entries).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<u64>", into = "Vec<u64>")]
+pub struct Prehashes(Vec<u64>);
+
+impl Prehashes {
+    /// Returns the pre-hashes in ascending order.
+    #[inline]
+    pub fn as_slice(&self) -> &[u64] {
+        &self.0
+    }
+
+    /// Returns the number of pre-hashes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no pre-hashes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the pre-hashes present here but not in `other`, identifying the elements that
+    /// `other`'s collection is missing.
+    pub fn difference(&self, other: &Prehashes) -> Prehashes {
+        let mut difference = Vec::new();
+        let mut theirs = other.0.iter().peekable();
+        for &mine in &self.0 {
+            while theirs.next_if(|&&h| h < mine).is_some() {}
+            match theirs.peek() {
+                Some(&&h) if h == mine => {
+                    theirs.next();
+                }
+                _ => difference.push(mine),
+            }
+        }
+        Prehashes(difference)
+    }
+
+    fn contains(&self, prehash: u64) -> bool {
+        self.0.binary_search(&prehash).is_ok()
+    }
+}
+
+impl From<Vec<u64>> for Prehashes {
+    /// Sorts `prehashes`, for example after receiving them from an untrusted peer.
+    fn from(mut prehashes: Vec<u64>) -> Self {
+        prehashes.sort_unstable();
+        Prehashes(prehashes)
+    }
+}
+
+impl From<Prehashes> for Vec<u64> {
+    #[inline]
+    fn from(prehashes: Prehashes) -> Self {
+        prehashes.0
+    }
+}
+
+impl<V: Hash, S> HashableHashSet<V, S> {
+    /// Returns the sorted pre-hashes of the elements.
+    pub fn prehashes(&self) -> Prehashes {
+        Prehashes::from(self.iter().map(stable_prehash).collect::<Vec<_>>())
+    }
+
+    /// Returns the elements whose pre-hashes are in `prehashes`.
+    pub fn select_prehashes(&self, prehashes: &Prehashes) -> Vec<&V> {
+        self.iter().filter(|v| prehashes.contains(stable_prehash(*v))).collect()
+    }
+}
+
+impl<K: Hash, V: Hash, S> HashableHashMap<K, V, S> {
+    /// Returns the sorted pre-hashes of the entries. An entry's pre-hash covers both its key and
+    /// its value, so a changed value shows up as a differing entry.
+    pub fn prehashes(&self) -> Prehashes {
+        Prehashes::from(self.iter().map(|entry| stable_prehash(&entry)).collect::<Vec<_>>())
+    }
+
+    /// Returns the entries whose pre-hashes are in `prehashes`.
+    pub fn select_prehashes(&self, prehashes: &Prehashes) -> Vec<(&K, &V)> {
+        self.iter().filter(|entry| prehashes.contains(stable_prehash(entry))).collect()
+    }
+}
+
+#[cfg(test)]
+mod prehashes_test {
+    use super::Prehashes;
+    use crate::{HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn equality_and_difference() {
+        let a = HashableHashSet::from([1, 2, 3]);
+        let b = HashableHashSet::from([3, 2, 1]);
+        assert_eq!(a.prehashes(), b.prehashes());
+        assert!(a.prehashes().difference(&b.prehashes()).is_empty());
+
+        let old = HashableHashMap::from([("a", 1), ("b", 2)]);
+        let new = HashableHashMap::from([("a", 1), ("b", 3)]);
+        assert_eq!(new.select_prehashes(&new.prehashes().difference(&old.prehashes())), vec![(&"b", &3)]);
+
+        // Pre-hashes received from another process, which holds only the element 1u32.
+        let remote = Prehashes::from(vec![16040831228450459584]);
+        let local = HashableHashSet::from([1u32, 2]);
+        assert_eq!(local.select_prehashes(&local.prehashes().difference(&remote)), vec![&2]);
+    }
+
+    #[test]
+    fn serde_sorts() {
+        let prehashes: Prehashes = serde_json::from_str("[3,1,2]").unwrap();
+        assert_eq!(prehashes.as_slice(), [1, 2, 3]);
+        assert_eq!(serde_json::to_string(&prehashes).unwrap(), "[1,2,3]");
+    }
+}
@@ -0,0 +1,190 @@
+//! [`rkyv`] support. The archived forms wrap rkyv's own hash tables and, like the unarchived
+//! wrappers, implement [`Hash`] independently of iteration order, so archived collections can be
+//! nested inside other archived hashable collections.
+
+use crate::{hash_prehashes, prehash, HashableHashMap, HashableHashSet};
+use rkyv::collections::swiss_table::{ArchivedHashMap, ArchivedHashSet, HashMapResolver, HashSetResolver};
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Deref;
+
+/// The archived form of [`HashableHashSet`].
+#[derive(Portable, rkyv::bytecheck::CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(transparent)]
+pub struct ArchivedHashableHashSet<V>(ArchivedHashSet<V>);
+
+impl<V> Deref for ArchivedHashableHashSet<V> {
+    type Target = ArchivedHashSet<V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V: Debug> Debug for ArchivedHashableHashSet<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<V: Hash + Eq> Eq for ArchivedHashableHashSet<V> {}
+
+impl<V: Hash> Hash for ArchivedHashableHashSet<V> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(prehash), hasher);
+    }
+}
+
+impl<V: Hash + Eq> PartialEq for ArchivedHashableHashSet<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<V, S> Archive for HashableHashSet<V, S>
+where V: Archive + Hash + Eq,
+      V::Archived: Hash + Eq,
+{
+    type Archived = ArchivedHashableHashSet<V::Archived>;
+    type Resolver = HashSetResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: `ArchivedHashableHashSet` is a transparent wrapper.
+        let out = unsafe { out.cast_unchecked::<ArchivedHashSet<V::Archived>>() };
+        self.0.resolve(resolver, out);
+    }
+}
+
+impl<V, S, Ser> Serialize<Ser> for HashableHashSet<V, S>
+where V: Serialize<Ser> + Hash + Eq,
+      V::Archived: Hash + Eq,
+      Ser: Fallible + Allocator + Writer + ?Sized,
+      Ser::Error: Source,
+{
+    fn serialize(&self, serializer: &mut Ser) -> Result<Self::Resolver, Ser::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<V, S, D> Deserialize<HashableHashSet<V, S>, D> for ArchivedHashableHashSet<V::Archived>
+where V: Archive + Hash + Eq,
+      V::Archived: Deserialize<V, D> + Hash + Eq,
+      S: BuildHasher + Default,
+      D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<HashableHashSet<V, S>, D::Error> {
+        self.0.deserialize(deserializer).map(HashableHashSet)
+    }
+}
+
+/// The archived form of [`HashableHashMap`].
+#[derive(Portable, rkyv::bytecheck::CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(transparent)]
+pub struct ArchivedHashableHashMap<K, V>(ArchivedHashMap<K, V>);
+
+impl<K, V> Deref for ArchivedHashableHashMap<K, V> {
+    type Target = ArchivedHashMap<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for ArchivedHashableHashMap<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for ArchivedHashableHashMap<K, V> {}
+
+impl<K: Hash, V: Hash> Hash for ArchivedHashableHashMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), state);
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for ArchivedHashableHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<K, V, S> Archive for HashableHashMap<K, V, S>
+where K: Archive + Hash + Eq,
+      K::Archived: Hash + Eq,
+      V: Archive,
+{
+    type Archived = ArchivedHashableHashMap<K::Archived, V::Archived>;
+    type Resolver = HashMapResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: `ArchivedHashableHashMap` is a transparent wrapper.
+        let out = unsafe { out.cast_unchecked::<ArchivedHashMap<K::Archived, V::Archived>>() };
+        self.0.resolve(resolver, out);
+    }
+}
+
+impl<K, V, S, Ser> Serialize<Ser> for HashableHashMap<K, V, S>
+where K: Serialize<Ser> + Hash + Eq,
+      K::Archived: Hash + Eq,
+      V: Serialize<Ser>,
+      Ser: Fallible + Allocator + Writer + ?Sized,
+      Ser::Error: Source,
+{
+    fn serialize(&self, serializer: &mut Ser) -> Result<Self::Resolver, Ser::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<K, V, S, D> Deserialize<HashableHashMap<K, V, S>, D> for ArchivedHashableHashMap<K::Archived, V::Archived>
+where K: Archive + Hash + Eq,
+      K::Archived: Deserialize<K, D> + Hash + Eq,
+      V: Archive,
+      V::Archived: Deserialize<V, D>,
+      S: BuildHasher + Default,
+      D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<HashableHashMap<K, V, S>, D::Error> {
+        self.0.deserialize(deserializer).map(HashableHashMap)
+    }
+}
+
+#[cfg(test)]
+mod rkyv_impls_test {
+    use super::{ArchivedHashableHashMap, ArchivedHashableHashSet};
+    use crate::{HashableHashMap, HashableHashSet};
+    use rkyv::rancor::Error;
+    use rkyv::string::ArchivedString;
+
+    type State = HashableHashMap<String, HashableHashSet<HashableHashSet<u32>>>;
+    type ArchivedState = ArchivedHashableHashMap<
+        ArchivedString,
+        ArchivedHashableHashSet<ArchivedHashableHashSet<rkyv::Archived<u32>>>>;
+
+    fn state() -> State {
+        let mut inner = HashableHashSet::new();
+        inner.insert(vec![1, 2].into_iter().collect());
+        inner.insert(vec![3].into_iter().collect());
+
+        let mut state = HashableHashMap::new();
+        state.insert("key".to_string(), inner);
+        state
+    }
+
+    #[test]
+    fn nested_round_trip() {
+        let bytes = rkyv::to_bytes::<Error>(&state()).unwrap();
+        let archived = rkyv::access::<ArchivedState, Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived.get("key").unwrap().len(), 2);
+
+        let deserialized: State = rkyv::deserialize::<State, Error>(archived).unwrap();
+        assert_eq!(deserialized, state());
+    }
+}
@@ -0,0 +1,138 @@
+//! A hashable wrapper around [`roaring`]'s compressed bitmap.
+//!
+//! Because a bitmap iterates in ascending order, [`HashableRoaringBitmap`] can hash its values in
+//! sequence rather than pre-hashing and sorting them, which matters for bitmaps with millions of
+//! values. As a result its hash differs from that of a [`crate::HashableHashSet<u32>`] with the
+//! same values.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::roaring::HashableRoaringBitmap;
+//! use std::collections::HashSet;
+//!
+//! let ids: HashableRoaringBitmap = (0..1_000_000).collect();
+//! let mut seen = HashSet::new();
+//! assert!(seen.insert(ids.clone()));
+//! assert!(!seen.insert(ids));
+//! ```
+
+use ::roaring::RoaringBitmap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`RoaringBitmap`] wrapper that implements [`Hash`] over its values in ascending order.
+#[derive(Clone, Default)]
+#[repr(transparent)]
+pub struct HashableRoaringBitmap(RoaringBitmap);
+
+impl HashableRoaringBitmap {
+    #[inline]
+    pub fn new() -> HashableRoaringBitmap {
+        Default::default()
+    }
+
+    /// Consumes the wrapper, returning the inner [`RoaringBitmap`].
+    #[inline]
+    pub fn into_inner(self) -> RoaringBitmap {
+        self.0
+    }
+}
+
+impl Debug for HashableRoaringBitmap {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl Deref for HashableRoaringBitmap {
+    type Target = RoaringBitmap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HashableRoaringBitmap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Eq for HashableRoaringBitmap {}
+
+impl Extend<u32> for HashableRoaringBitmap {
+    #[inline]
+    fn extend<T: IntoIterator<Item=u32>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl From<RoaringBitmap> for HashableRoaringBitmap {
+    #[inline]
+    fn from(bitmap: RoaringBitmap) -> Self {
+        HashableRoaringBitmap(bitmap)
+    }
+}
+
+impl FromIterator<u32> for HashableRoaringBitmap {
+    fn from_iter<T: IntoIterator<Item=u32>>(iter: T) -> Self {
+        HashableRoaringBitmap(RoaringBitmap::from_iter(iter))
+    }
+}
+
+impl Hash for HashableRoaringBitmap {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.0.len());
+        self.0.iter().for_each(|v| hasher.write_u32(v));
+    }
+}
+
+impl<'a> IntoIterator for &'a HashableRoaringBitmap {
+    type Item = u32;
+    type IntoIter = ::roaring::bitmap::Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> ::roaring::bitmap::Iter<'a> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for HashableRoaringBitmap {
+    type Item = u32;
+    type IntoIter = ::roaring::bitmap::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> ::roaring::bitmap::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl PartialEq for HashableRoaringBitmap {
+    fn eq(&self, other: &Self) -> bool {
+        // The bitmaps compare containers structurally, so equal values stored in differently
+        // shaped containers need the slower elementwise comparison.
+        self.0 == other.0 || self.0.len() == other.0.len() && self.0.iter().eq(other.0.iter())
+    }
+}
+
+#[cfg(test)]
+mod roaring_test {
+    use super::HashableRoaringBitmap;
+    use crate::hash;
+
+    #[test]
+    fn hash_is_consistent_with_eq() {
+        let mut a: HashableRoaringBitmap = (0..10_000).collect();
+        a.remove_range(100..10_000);
+        let b: HashableRoaringBitmap = (0..100).collect();
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let c: HashableRoaringBitmap = (1..101).collect();
+        assert_ne!(a, c);
+        assert_ne!(hash(&a), hash(&c));
+    }
+}
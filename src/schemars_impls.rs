@@ -0,0 +1,58 @@
+//! [`JsonSchema`] implementations that delegate to the schemas of the inner std collections.
+
+use crate::{HashableHashMap, HashableHashSet};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+impl<V: JsonSchema, S> JsonSchema for HashableHashSet<V, S> {
+    fn inline_schema() -> bool {
+        HashSet::<V, S>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        HashSet::<V, S>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        HashSet::<V, S>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        HashSet::<V, S>::json_schema(generator)
+    }
+}
+
+impl<K: JsonSchema, V: JsonSchema, S> JsonSchema for HashableHashMap<K, V, S> {
+    fn inline_schema() -> bool {
+        HashMap::<K, V, S>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        HashMap::<K, V, S>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        HashMap::<K, V, S>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        HashMap::<K, V, S>::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod schemars_impls_test {
+    use crate::{HashableHashMap, HashableHashSet};
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn schemas_match_std() {
+        assert_eq!(
+            schemars::schema_for!(HashableHashSet<u32>),
+            schemars::schema_for!(HashSet<u32>));
+        assert_eq!(
+            schemars::schema_for!(HashableHashMap<String, HashableHashSet<u32>>),
+            schemars::schema_for!(HashMap<String, HashSet<u32>>));
+    }
+}
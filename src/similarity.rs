@@ -0,0 +1,65 @@
+//! Similarity metrics between sets.
+
+use crate::HashableHashSet;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+impl<V: Eq + Hash, S: BuildHasher> HashableHashSet<V, S> {
+    /// Returns the Jaccard index `|A ∩ B| / |A ∪ B|`. Two empty sets have a similarity of `1.0`.
+    pub fn jaccard<S2: BuildHasher>(&self, other: &HashSet<V, S2>) -> f64 {
+        let (intersection, len, other_len) = self.overlap_counts(other);
+        let union = len + other_len - intersection;
+        if union == 0 { 1.0 } else { intersection as f64 / union as f64 }
+    }
+
+    /// Returns the overlap coefficient `|A ∩ B| / min(|A|, |B|)`, which is `1.0` whenever one set
+    /// is a subset of the other, including when either is empty.
+    pub fn overlap_coefficient<S2: BuildHasher>(&self, other: &HashSet<V, S2>) -> f64 {
+        let (intersection, len, other_len) = self.overlap_counts(other);
+        let min = len.min(other_len);
+        if min == 0 { 1.0 } else { intersection as f64 / min as f64 }
+    }
+
+    /// Returns the Sørensen–Dice coefficient `2|A ∩ B| / (|A| + |B|)`. Two empty sets have a
+    /// similarity of `1.0`.
+    pub fn dice<S2: BuildHasher>(&self, other: &HashSet<V, S2>) -> f64 {
+        let (intersection, len, other_len) = self.overlap_counts(other);
+        let total = len + other_len;
+        if total == 0 { 1.0 } else { 2.0 * intersection as f64 / total as f64 }
+    }
+
+    /// Returns the intersection size and both set sizes, counting in one pass over the smaller set.
+    fn overlap_counts<S2: BuildHasher>(&self, other: &HashSet<V, S2>) -> (usize, usize, usize) {
+        let intersection = if self.len() <= other.len() {
+            self.iter().filter(|v| other.contains(*v)).count()
+        } else {
+            other.iter().filter(|v| self.contains(*v)).count()
+        };
+        (intersection, self.len(), other.len())
+    }
+}
+
+#[cfg(test)]
+mod similarity_test {
+    use crate::HashableHashSet;
+
+    #[test]
+    fn metrics() {
+        let a = HashableHashSet::from([1, 2, 3, 4]);
+        let b = HashableHashSet::from([3, 4, 5]);
+        assert_eq!(a.jaccard(&b), 2.0 / 5.0);
+        assert_eq!(a.overlap_coefficient(&b), 2.0 / 3.0);
+        assert_eq!(a.dice(&b), 4.0 / 7.0);
+        assert_eq!(b.jaccard(&a), a.jaccard(&b));
+    }
+
+    #[test]
+    fn empty_sets() {
+        let empty = HashableHashSet::<u32>::new();
+        let a = HashableHashSet::from([1]);
+        assert_eq!(empty.jaccard(&empty), 1.0);
+        assert_eq!(empty.dice(&empty), 1.0);
+        assert_eq!(a.jaccard(&empty), 0.0);
+        assert_eq!(a.overlap_coefficient(&empty), 1.0);
+    }
+}
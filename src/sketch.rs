@@ -0,0 +1,343 @@
+//! Probabilistic summaries of [`HashableHashSet`]s.
+//!
+//! Sketches are derived from a fixed hash of each element rather than the per-process pre-hashes
+//! behind [`Hash`], so sketches built by different processes, or on different platforms, can be
+//! compared and merged with one another.
+
+use crate::stable::stable_prehash;
+use crate::HashableHashSet;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+/// Derives the `i`-th of a family of hash functions from an element pre-hash.
+pub(crate) fn mix(prehash: u64, i: u64) -> u64 {
+    // The SplitMix64 finalizer.
+    let mut z = prehash ^ i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A MinHash signature, which estimates the Jaccard similarity of the sets it was built from.
+/// Returned by [`HashableHashSet::minhash`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MinHash {
+    mins: Vec<u64>,
+}
+
+impl MinHash {
+    /// Returns the number of hash functions in the signature.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.mins.len()
+    }
+
+    /// Returns `true` if the signature has no hash functions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mins.is_empty()
+    }
+
+    /// Estimates the Jaccard similarity of the sets behind two signatures. The standard error is
+    /// about `1 / sqrt(k)` for signatures of length `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures have different lengths.
+    pub fn estimated_jaccard(&self, other: &MinHash) -> f64 {
+        assert_eq!(self.len(), other.len(), "MinHash signatures must have the same length");
+        if self.is_empty() {
+            return 1.0;
+        }
+        let matching = self.mins.iter().zip(&other.mins).filter(|(a, b)| a == b).count();
+        matching as f64 / self.len() as f64
+    }
+}
+
+/// A Bloom filter, a compact membership test that can report false positives but never false
+/// negatives. Returned by [`HashableHashSet::to_bloom_filter`].
+///
+/// The bits set for an element are determined by a fixed hash of it, so a filter that was
+/// serialized in one process answers lookups correctly in any other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "BloomFilterFields")]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+// Deserialization goes through this unchecked twin so that inconsistent input is rejected rather
+// than causing panics on lookup.
+#[derive(Deserialize)]
+struct BloomFilterFields {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl TryFrom<BloomFilterFields> for BloomFilter {
+    type Error = &'static str;
+
+    fn try_from(fields: BloomFilterFields) -> Result<Self, Self::Error> {
+        if fields.num_bits == 0 || fields.bits.len() as u64 != fields.num_bits.div_ceil(64) {
+            return Err("Bloom filter bit count does not match its bits");
+        }
+        Ok(BloomFilter { bits: fields.bits, num_bits: fields.num_bits, num_hashes: fields.num_hashes })
+    }
+}
+
+impl BloomFilter {
+    /// Returns `false` if `value` was definitely not in the set, and `true` if it probably was.
+    pub fn contains<Q: Hash + ?Sized>(&self, value: &Q) -> bool {
+        bit_indices(stable_prehash(value), self.num_bits, self.num_hashes).all(|i| self.bits[(i / 64) as usize] & (1 << (i % 64)) != 0)
+    }
+
+    /// Returns the size of the filter in bits.
+    #[inline]
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// Returns the number of bits set per element.
+    #[inline]
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    fn insert_prehash(&mut self, prehash: u64) {
+        for i in bit_indices(prehash, self.num_bits, self.num_hashes) {
+            self.bits[(i / 64) as usize] |= 1 << (i % 64);
+        }
+    }
+}
+
+/// Returns the bits of a Bloom filter to set for an element, using double hashing so that only
+/// two independent hashes are needed for any number of bits.
+fn bit_indices(prehash: u64, num_bits: u64, num_hashes: u32) -> impl Iterator<Item=u64> {
+    let h2 = mix(prehash, 1) | 1;
+    (0..u64::from(num_hashes)).map(move |i| prehash.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+}
+
+/// A HyperLogLog sketch, which estimates the number of distinct elements it has seen. Sketches
+/// with the same precision can be merged to estimate the cardinality of a union. Returned by
+/// [`HashableHashSet::to_hll`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "HyperLogLogFields")]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+// See `BloomFilterFields`.
+#[derive(Deserialize)]
+struct HyperLogLogFields {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl TryFrom<HyperLogLogFields> for HyperLogLog {
+    type Error = &'static str;
+
+    fn try_from(fields: HyperLogLogFields) -> Result<Self, Self::Error> {
+        if !HyperLogLog::PRECISIONS.contains(&fields.precision)
+            || fields.registers.len() != 1 << fields.precision {
+            return Err("HyperLogLog precision does not match its registers");
+        }
+        Ok(HyperLogLog { precision: fields.precision, registers: fields.registers })
+    }
+}
+
+impl HyperLogLog {
+    /// The supported precisions. A sketch with precision `p` uses `2^p` bytes and has a standard
+    /// error of about `1.04 / sqrt(2^p)`.
+    pub const PRECISIONS: std::ops::RangeInclusive<u8> = 4..=18;
+
+    /// Creates an empty sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not in [`PRECISIONS`](Self::PRECISIONS).
+    pub fn new(precision: u8) -> Self {
+        assert!(Self::PRECISIONS.contains(&precision), "unsupported HyperLogLog precision {}", precision);
+        HyperLogLog { precision, registers: vec![0; 1 << precision] }
+    }
+
+    /// Returns the precision of the sketch.
+    #[inline]
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Records a value.
+    pub fn insert<Q: Hash + ?Sized>(&mut self, value: &Q) {
+        self.insert_prehash(stable_prehash(value));
+    }
+
+    /// Merges another sketch into this one, after which this sketch estimates the cardinality of
+    /// the union.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have different precisions.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(self.precision, other.precision, "HyperLogLog sketches must have the same precision");
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+    }
+
+    /// Estimates the number of distinct values recorded.
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-i32::from(r))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // Linear counting is more accurate while many registers are still empty.
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    fn insert_prehash(&mut self, prehash: u64) {
+        let hash = mix(prehash, 2);
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rank = ((hash << self.precision).leading_zeros() + 1).min(u32::from(64 - self.precision) + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+}
+
+impl<V: Hash, S> HashableHashSet<V, S> {
+    /// Builds a MinHash signature with `k` hash functions.
+    pub fn minhash(&self, k: usize) -> MinHash {
+        let mut mins = vec![u64::MAX; k];
+        for v in self.iter() {
+            let prehash = stable_prehash(v);
+            for (i, min) in mins.iter_mut().enumerate() {
+                *min = (*min).min(mix(prehash, i as u64));
+            }
+        }
+        MinHash { mins }
+    }
+
+    /// Builds a Bloom filter over the elements, sized so that lookups of values not in the set
+    /// return `true` with probability about `false_positive_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `false_positive_rate` is strictly between `0.0` and `1.0`.
+    pub fn to_bloom_filter(&self, false_positive_rate: f64) -> BloomFilter {
+        assert!(0.0 < false_positive_rate && false_positive_rate < 1.0,
+                "false_positive_rate must be between 0 and 1, got {}", false_positive_rate);
+        let n = self.len().max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(1.0) as u64;
+        let num_hashes = (num_bits as f64 / n * ln2).round().max(1.0) as u32;
+        let mut filter = BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        };
+        for v in self.iter() {
+            filter.insert_prehash(stable_prehash(v));
+        }
+        filter
+    }
+
+    /// Builds a HyperLogLog sketch of the elements with the given precision.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not in [`HyperLogLog::PRECISIONS`].
+    pub fn to_hll(&self, precision: u8) -> HyperLogLog {
+        let mut hll = HyperLogLog::new(precision);
+        for v in self.iter() {
+            hll.insert_prehash(stable_prehash(v));
+        }
+        hll
+    }
+}
+
+#[cfg(test)]
+mod sketch_test {
+    use super::{BloomFilter, HyperLogLog};
+    use crate::HashableHashSet;
+
+    #[test]
+    fn minhash() {
+        let a: HashableHashSet<u32> = (0..1000).collect();
+        let b: HashableHashSet<u32> = (500..1500).collect();
+        let estimate = a.minhash(256).estimated_jaccard(&b.minhash(256));
+        assert!((estimate - 1.0 / 3.0).abs() < 0.1, "estimate: {}", estimate);
+
+        assert_eq!(a.minhash(64), a.clone().minhash(64));
+        assert_eq!(a.minhash(64).estimated_jaccard(&a.minhash(64)), 1.0);
+
+        // Signatures must not depend on the process that built them.
+        let json = serde_json::to_string(&HashableHashSet::from([1u32, 2]).minhash(2)).unwrap();
+        assert_eq!(json, r#"{"mins":[3865573954175490644,6507281332327306007]}"#);
+    }
+
+    #[test]
+    fn bloom_filter() {
+        let set: HashableHashSet<u32> = (0..1000).collect();
+        let filter = set.to_bloom_filter(0.01);
+        assert!(set.iter().all(|v| filter.contains(v)));
+        let false_positives = (1000..11000).filter(|v| filter.contains(v)).count();
+        assert!(false_positives < 200, "false positives: {}", false_positives);
+
+        let names = HashableHashSet::from(["a".to_string()]).to_bloom_filter(0.01);
+        assert!(names.contains("a"));
+    }
+
+    #[test]
+    fn bloom_filter_serde() {
+        let filter = HashableHashSet::from([1, 2, 3]).to_bloom_filter(0.01);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(serde_json::from_str::<BloomFilter>(&json).unwrap(), filter);
+        assert!(serde_json::from_str::<BloomFilter>(r#"{"bits":[],"num_bits":0,"num_hashes":1}"#).is_err());
+        // The bits must not depend on the process that built the filter.
+        assert_eq!(json, r#"{"bits":[360660338],"num_bits":29,"num_hashes":7}"#);
+    }
+
+    #[test]
+    fn hyper_log_log() {
+        let a: HashableHashSet<u32> = (0..10_000).collect();
+        let b: HashableHashSet<u32> = (5_000..20_000).collect();
+        let mut union = a.to_hll(12);
+        union.merge(&b.to_hll(12));
+        let estimate = union.estimate();
+        assert!((estimate - 20_000.0).abs() < 1_000.0, "estimate: {}", estimate);
+
+        assert_eq!(HyperLogLog::new(8).estimate(), 0.0);
+        let small = HashableHashSet::from([1, 2, 3]).to_hll(8).estimate();
+        assert!((small - 3.0).abs() < 0.5, "estimate: {}", small);
+        assert!(serde_json::from_str::<HyperLogLog>(r#"{"precision":4,"registers":[0]}"#).is_err());
+    }
+
+    #[test]
+    fn hyper_log_log_merges_across_hashers() {
+        // Shards with differently seeded sets, standing in for separate processes, must agree on
+        // which elements they have in common.
+        let mut a = HashableHashSet::with_hasher(ahash::RandomState::with_seeds(1, 2, 3, 4));
+        let mut b = HashableHashSet::with_hasher(ahash::RandomState::with_seeds(5, 6, 7, 8));
+        a.extend(0..1_000u32);
+        b.extend(0..1_000u32);
+        let mut union = a.to_hll(12);
+        union.merge(&b.to_hll(12));
+        assert_eq!(union, a.to_hll(12));
+
+        let json = serde_json::to_string(&HashableHashSet::from([1u32]).to_hll(4)).unwrap();
+        assert_eq!(json, r#"{"precision":4,"registers":[0,0,0,0,0,0,0,0,0,0,0,0,3,0,0,0]}"#);
+    }
+}
@@ -0,0 +1,284 @@
+//! Hashable wrappers around [`slotmap`]'s secondary maps.
+//!
+//! [`HashableSecondaryMap`] and [`HashableSparseSecondaryMap`] hash and compare only the entries
+//! that are present, independently of slot layout and order, and hash like a
+//! [`crate::HashableHashMap`] holding the same entries. This allows state built on slot maps to
+//! be fingerprinted, for example to detect divergence in rollback netcode.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::slotmap::HashableSecondaryMap;
+//! use slotmap::{DefaultKey, SlotMap};
+//!
+//! let mut entities: SlotMap<DefaultKey, ()> = SlotMap::new();
+//! let (a, b) = (entities.insert(()), entities.insert(()));
+//! let mut health: HashableSecondaryMap<DefaultKey, u32> = HashableSecondaryMap::new();
+//! health.insert(b, 80);
+//! health.insert(a, 100);
+//! let other: HashableSecondaryMap<DefaultKey, u32> = vec![(a, 100), (b, 80)].into_iter().collect();
+//! assert_eq!(health, other);
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::slotmap::{Key, SecondaryMap, SparseSecondaryMap};
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A [`SecondaryMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableSecondaryMap<K: Key, V>(SecondaryMap<K, V>);
+
+impl<K: Key, V> HashableSecondaryMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableSecondaryMap<K, V> {
+        HashableSecondaryMap(SecondaryMap::new())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableSecondaryMap<K, V> {
+        HashableSecondaryMap(SecondaryMap::with_capacity(capacity))
+    }
+
+    /// Consumes the wrapper, returning the inner [`SecondaryMap`].
+    #[inline]
+    pub fn into_inner(self) -> SecondaryMap<K, V> {
+        self.0
+    }
+}
+
+impl<K: Key, V: Debug> Debug for HashableSecondaryMap<K, V> {
+    /// Lists the present entries, unlike [`SecondaryMap`]'s own implementation.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
+    }
+}
+
+impl<K: Key, V> Default for HashableSecondaryMap<K, V> {
+    #[inline]
+    fn default() -> Self {
+        HashableSecondaryMap::new()
+    }
+}
+
+impl<K: Key, V> Deref for HashableSecondaryMap<K, V> {
+    type Target = SecondaryMap<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K: Key, V> DerefMut for HashableSecondaryMap<K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Key, V: Eq> Eq for HashableSecondaryMap<K, V> {}
+
+impl<K: Key, V> Extend<(K, V)> for HashableSecondaryMap<K, V> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K: Key, V> From<SecondaryMap<K, V>> for HashableSecondaryMap<K, V> {
+    #[inline]
+    fn from(map: SecondaryMap<K, V>) -> Self {
+        HashableSecondaryMap(map)
+    }
+}
+
+impl<K: Key, V> FromIterator<(K, V)> for HashableSecondaryMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableSecondaryMap(SecondaryMap::from_iter(iter))
+    }
+}
+
+impl<K: Key, V: Hash> Hash for HashableSecondaryMap<K, V> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), hasher);
+    }
+}
+
+impl<'a, K: Key, V> IntoIterator for &'a HashableSecondaryMap<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = ::slotmap::secondary::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::slotmap::secondary::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: Key, V> IntoIterator for HashableSecondaryMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = ::slotmap::secondary::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::slotmap::secondary::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Key, V: PartialEq> PartialEq for HashableSecondaryMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// A [`SparseSecondaryMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableSparseSecondaryMap<K: Key, V, S: BuildHasher = RandomState>(SparseSecondaryMap<K, V, S>);
+
+impl<K: Key, V> HashableSparseSecondaryMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableSparseSecondaryMap<K, V> {
+        HashableSparseSecondaryMap(SparseSecondaryMap::new())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableSparseSecondaryMap<K, V> {
+        HashableSparseSecondaryMap(SparseSecondaryMap::with_capacity(capacity))
+    }
+}
+
+impl<K: Key, V, S: BuildHasher> HashableSparseSecondaryMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableSparseSecondaryMap(SparseSecondaryMap::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableSparseSecondaryMap(SparseSecondaryMap::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Consumes the wrapper, returning the inner [`SparseSecondaryMap`].
+    #[inline]
+    pub fn into_inner(self) -> SparseSecondaryMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: Key, V: Debug, S: BuildHasher> Debug for HashableSparseSecondaryMap<K, V, S> {
+    /// Lists the present entries, unlike [`SparseSecondaryMap`]'s own implementation.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
+    }
+}
+
+impl<K: Key, V, S: BuildHasher + Default> Default for HashableSparseSecondaryMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableSparseSecondaryMap(SparseSecondaryMap::default())
+    }
+}
+
+impl<K: Key, V, S: BuildHasher> Deref for HashableSparseSecondaryMap<K, V, S> {
+    type Target = SparseSecondaryMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K: Key, V, S: BuildHasher> DerefMut for HashableSparseSecondaryMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: Key, V: Eq, S: BuildHasher> Eq for HashableSparseSecondaryMap<K, V, S> {}
+
+impl<K: Key, V, S: BuildHasher> Extend<(K, V)> for HashableSparseSecondaryMap<K, V, S> {
+    #[inline]
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K: Key, V, S: BuildHasher> From<SparseSecondaryMap<K, V, S>> for HashableSparseSecondaryMap<K, V, S> {
+    #[inline]
+    fn from(map: SparseSecondaryMap<K, V, S>) -> Self {
+        HashableSparseSecondaryMap(map)
+    }
+}
+
+impl<K: Key, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashableSparseSecondaryMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableSparseSecondaryMap(SparseSecondaryMap::from_iter(iter))
+    }
+}
+
+impl<K: Key, V: Hash, S: BuildHasher> Hash for HashableSparseSecondaryMap<K, V, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(|entry| prehash(&entry)), hasher);
+    }
+}
+
+impl<'a, K: Key, V, S: BuildHasher> IntoIterator for &'a HashableSparseSecondaryMap<K, V, S> {
+    type Item = (K, &'a V);
+    type IntoIter = ::slotmap::sparse_secondary::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::slotmap::sparse_secondary::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: Key, V, S: BuildHasher> IntoIterator for HashableSparseSecondaryMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = ::slotmap::sparse_secondary::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::slotmap::sparse_secondary::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Key, V: PartialEq, S: BuildHasher> PartialEq for HashableSparseSecondaryMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod slotmap_test {
+    use super::{HashableSecondaryMap, HashableSparseSecondaryMap};
+    use crate::{hash, HashableHashMap};
+    use slotmap::{DefaultKey, SlotMap};
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let mut slots: SlotMap<DefaultKey, ()> = SlotMap::new();
+        let keys: Vec<DefaultKey> = (0..3).map(|_| slots.insert(())).collect();
+
+        let dense: HashableSecondaryMap<DefaultKey, usize> = keys.iter().rev().map(|&k| (k, 1)).collect();
+        let sparse: HashableSparseSecondaryMap<DefaultKey, usize> = keys.iter().map(|&k| (k, 1)).collect();
+        let std: HashableHashMap<DefaultKey, usize> = keys.iter().map(|&k| (k, 1)).collect();
+        assert_eq!(hash(&dense), hash(&std));
+        assert_eq!(hash(&sparse), hash(&std));
+    }
+
+    #[test]
+    fn ignores_removed_entries() {
+        let mut slots: SlotMap<DefaultKey, ()> = SlotMap::new();
+        let (a, b) = (slots.insert(()), slots.insert(()));
+
+        let mut with_removal = HashableSecondaryMap::with_capacity(8);
+        with_removal.insert(a, "a");
+        with_removal.insert(b, "b");
+        with_removal.remove(b);
+        let only_a: HashableSecondaryMap<DefaultKey, &str> = vec![(a, "a")].into_iter().collect();
+        assert_eq!(with_removal, only_a);
+        assert_eq!(hash(&with_removal), hash(&only_a));
+    }
+}
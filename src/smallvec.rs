@@ -0,0 +1,348 @@
+//! A small-size-optimized set built on [`smallvec`].
+//!
+//! [`SmallHashableHashSet`] stores up to `N` elements inline, without a heap allocation, and
+//! spills into a hash table once it grows beyond that. Either way it hashes like a
+//! [`crate::HashableHashSet`] with the same elements.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::smallvec::SmallHashableHashSet;
+//! use hashable::HashableHashSet;
+//! use std::collections::hash_map::DefaultHasher;
+//! use std::hash::{Hash, Hasher};
+//!
+//! fn hash<T: Hash>(value: &T) -> u64 {
+//!     let mut hasher = DefaultHasher::new();
+//!     value.hash(&mut hasher);
+//!     hasher.finish()
+//! }
+//!
+//! let small: SmallHashableHashSet<u32, 4> = vec![1, 2, 3].into_iter().collect();
+//! assert!(!small.spilled());
+//! let set: HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+//! assert_eq!(hash(&small), hash(&set));
+//! ```
+
+use crate::{hash_prehashes, prehash, HashableHashSet};
+use ::smallvec::SmallVec;
+use std::borrow::Borrow;
+use std::collections::{hash_set, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+
+/// A set that stores up to `N` elements inline and spills into a [`HashSet`] beyond that.
+///
+/// Lookups in the inline representation are linear scans, so `N` should stay small. Once spilled,
+/// the set stays spilled until [`SmallHashableHashSet::shrink_to_fit`] is called.
+#[derive(Clone)]
+pub struct SmallHashableHashSet<V, const N: usize, S = ahash::RandomState>(Repr<V, N, S>);
+
+#[derive(Clone)]
+enum Repr<V, const N: usize, S> {
+    Inline(SmallVec<[V; N]>),
+    Spilled(HashSet<V, S>),
+}
+
+impl<V, const N: usize, S> SmallHashableHashSet<V, N, S> {
+    #[inline]
+    pub fn new() -> SmallHashableHashSet<V, N, S> {
+        SmallHashableHashSet(Repr::Inline(SmallVec::new()))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Inline(elements) => elements.len(),
+            Repr::Spilled(set) => set.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the elements are stored in a heap-allocated hash table.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        matches!(self.0, Repr::Spilled(_))
+    }
+
+    /// Iterates over the elements in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        match &self.0 {
+            Repr::Inline(elements) => Iter(IterRepr::Inline(elements.iter())),
+            Repr::Spilled(set) => Iter(IterRepr::Spilled(set.iter())),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = Repr::Inline(SmallVec::new());
+    }
+}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> SmallHashableHashSet<V, N, S> {
+    /// Adds a value, returning whether it was newly added. Spills into a hash table if the set
+    /// already holds `N` elements inline.
+    pub fn insert(&mut self, value: V) -> bool {
+        let elements = match &mut self.0 {
+            Repr::Inline(elements) => elements,
+            Repr::Spilled(set) => return set.insert(value),
+        };
+        if elements.contains(&value) {
+            return false;
+        }
+        if elements.len() < N {
+            elements.push(value);
+        } else {
+            let mut set = HashSet::with_capacity_and_hasher(N + 1, S::default());
+            set.extend(elements.drain(..));
+            set.insert(value);
+            self.0 = Repr::Spilled(set);
+        }
+        true
+    }
+
+    /// Removes a value, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        match &mut self.0 {
+            Repr::Inline(elements) => match elements.iter().position(|v| v.borrow() == value) {
+                Some(i) => {
+                    elements.swap_remove(i);
+                    true
+                }
+                None => false,
+            },
+            Repr::Spilled(set) => set.remove(value),
+        }
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Eq + Hash + ?Sized,
+    {
+        match &self.0 {
+            Repr::Inline(elements) => elements.iter().any(|v| v.borrow() == value),
+            Repr::Spilled(set) => set.contains(value),
+        }
+    }
+
+    /// Moves the elements back inline if they fit, releasing the hash table.
+    pub fn shrink_to_fit(&mut self) {
+        if let Repr::Spilled(set) = &mut self.0 {
+            if set.len() <= N {
+                self.0 = Repr::Inline(set.drain().collect());
+            } else {
+                set.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Converts into a [`HashableHashSet`], allocating if the elements are inline.
+    pub fn into_hashable_hash_set(self) -> HashableHashSet<V, S> {
+        match self.0 {
+            Repr::Inline(elements) => elements.into_iter().collect(),
+            Repr::Spilled(set) => HashableHashSet(set),
+        }
+    }
+}
+
+impl<V: Debug, const N: usize, S> Debug for SmallHashableHashSet<V, N, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<V, const N: usize, S> Default for SmallHashableHashSet<V, N, S> {
+    #[inline]
+    fn default() -> Self {
+        SmallHashableHashSet::new()
+    }
+}
+
+impl<'de, V, const N: usize, S> serde::Deserialize<'de> for SmallHashableHashSet<V, N, S>
+where V: Eq + Hash + serde::Deserialize<'de>,
+      S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Vec::deserialize(de).map(|elements: Vec<V>| elements.into_iter().collect())
+    }
+}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> Eq for SmallHashableHashSet<V, N, S> {}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> Extend<V> for SmallHashableHashSet<V, N, S> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> From<HashableHashSet<V, S>>
+    for SmallHashableHashSet<V, N, S>
+{
+    /// Moves the elements inline if they fit.
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        let mut small = SmallHashableHashSet(Repr::Spilled(set.0));
+        small.shrink_to_fit();
+        small
+    }
+}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> FromIterator<V> for SmallHashableHashSet<V, N, S> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        let mut set = SmallHashableHashSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<V: Hash, const N: usize, S> Hash for SmallHashableHashSet<V, N, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.iter().map(prehash), hasher);
+    }
+}
+
+impl<'a, V, const N: usize, S> IntoIterator for &'a SmallHashableHashSet<V, N, S> {
+    type Item = &'a V;
+    type IntoIter = Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, V> {
+        self.iter()
+    }
+}
+
+impl<V, const N: usize, S> IntoIterator for SmallHashableHashSet<V, N, S> {
+    type Item = V;
+    type IntoIter = IntoIter<V, N>;
+
+    fn into_iter(self) -> IntoIter<V, N> {
+        match self.0 {
+            Repr::Inline(elements) => IntoIter(IntoIterRepr::Inline(elements.into_iter())),
+            Repr::Spilled(set) => IntoIter(IntoIterRepr::Spilled(set.into_iter())),
+        }
+    }
+}
+
+impl<V: Eq + Hash, const N: usize, S: BuildHasher + Default> PartialEq for SmallHashableHashSet<V, N, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|v| other.contains(v))
+    }
+}
+
+impl<V, const N: usize, S> serde::Serialize for SmallHashableHashSet<V, N, S>
+where V: serde::Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[cfg(any(feature = "serde_json", feature = "ciborium"))]
+        if let Some(encoding) = crate::canonical::active() {
+            return crate::canonical::serialize_seq(encoding, self.iter(), ser);
+        }
+        ser.collect_seq(self.iter())
+    }
+}
+
+/// An iterator over the elements of a [`SmallHashableHashSet`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a, V>(IterRepr<'a, V>);
+
+#[derive(Clone, Debug)]
+enum IterRepr<'a, V> {
+    Inline(std::slice::Iter<'a, V>),
+    Spilled(hash_set::Iter<'a, V>),
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a V> {
+        match &mut self.0 {
+            IterRepr::Inline(iter) => iter.next(),
+            IterRepr::Spilled(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterRepr::Inline(iter) => iter.size_hint(),
+            IterRepr::Spilled(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<V> ExactSizeIterator for Iter<'_, V> {}
+
+/// An owning iterator over the elements of a [`SmallHashableHashSet`].
+pub struct IntoIter<V, const N: usize>(IntoIterRepr<V, N>);
+
+enum IntoIterRepr<V, const N: usize> {
+    Inline(::smallvec::IntoIter<[V; N]>),
+    Spilled(hash_set::IntoIter<V>),
+}
+
+impl<V, const N: usize> Iterator for IntoIter<V, N> {
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<V> {
+        match &mut self.0 {
+            IntoIterRepr::Inline(iter) => iter.next(),
+            IntoIterRepr::Spilled(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IntoIterRepr::Inline(iter) => iter.size_hint(),
+            IntoIterRepr::Spilled(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<V, const N: usize> ExactSizeIterator for IntoIter<V, N> {}
+
+#[cfg(test)]
+mod smallvec_test {
+    use super::SmallHashableHashSet;
+    use crate::{hash, HashableHashSet};
+
+    #[test]
+    fn hash_matches_std_wrapper() {
+        let small: SmallHashableHashSet<u32, 2> = vec![1, 2].into_iter().collect();
+        let std: HashableHashSet<u32> = vec![2, 1].into_iter().collect();
+        assert!(!small.spilled());
+        assert_eq!(hash(&small), hash(&std));
+
+        let spilled: SmallHashableHashSet<u32, 2> = vec![1, 2, 3].into_iter().collect();
+        let std: HashableHashSet<u32> = vec![3, 2, 1].into_iter().collect();
+        assert!(spilled.spilled());
+        assert_eq!(hash(&spilled), hash(&std));
+    }
+
+    #[test]
+    fn spill_and_shrink() {
+        let mut set: SmallHashableHashSet<u32, 2> = SmallHashableHashSet::new();
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(2));
+        assert!(set.insert(3));
+        assert!(set.spilled());
+        assert!(set.remove(&3));
+        let inline: SmallHashableHashSet<u32, 2> = vec![2, 1].into_iter().collect();
+        assert_eq!(set, inline);
+        set.shrink_to_fit();
+        assert!(!set.spilled());
+        assert!(set.contains(&1) && set.contains(&2));
+    }
+}
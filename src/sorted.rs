@@ -0,0 +1,226 @@
+//! Canonical serialization that emits entries sorted by element (or by key, for maps).
+//!
+//! Unlike [`unordered`](crate::unordered), the order depends only on [`Ord`], so the output bytes
+//! are identical across runs, hashers, and platforms. Use the [`Sorted`] marker type, or this
+//! module with `#[serde(with = "hashable::sorted")]`.
+//!
+//! The module also provides sorted iteration, such as [`HashableHashSet::iter_sorted`], for
+//! reports and golden files.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::{HashableHashSet, Sorted};
+//!
+//! let set: HashableHashSet<_> = vec![3, 1, 2].into_iter().collect();
+//! assert_eq!(serde_json::to_string(&Sorted(set)).unwrap(), "[1,2,3]");
+//! ```
+
+use crate::stable::stable_prehash;
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+
+/// A collection that can be serialized via [`serialize`] or [`Sorted`].
+pub trait SortedSerialize {
+    /// Serializes the collection with its entries in ascending order.
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error>;
+}
+
+impl<V: Ord + Serialize, S> SortedSerialize for HashSet<V, S> {
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable();
+        ser.collect_seq(entries)
+    }
+}
+
+impl<K: Ord + Serialize, V: Serialize, S> SortedSerialize for HashMap<K, V, S> {
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by_key(|(k, _)| *k);
+        ser.collect_map(entries)
+    }
+}
+
+impl<V: Ord + Serialize, S> SortedSerialize for HashableHashSet<V, S> {
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (**self).serialize_sorted(ser)
+    }
+}
+
+impl<K: Ord + Serialize, V: Serialize, S> SortedSerialize for HashableHashMap<K, V, S> {
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (**self).serialize_sorted(ser)
+    }
+}
+
+impl<T: SortedSerialize + ?Sized> SortedSerialize for &T {
+    fn serialize_sorted<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (**self).serialize_sorted(ser)
+    }
+}
+
+/// Serializes a collection with its entries in ascending order.
+pub fn serialize<T, Ser>(value: &T, ser: Ser) -> Result<Ser::Ok, Ser::Error>
+where T: SortedSerialize + ?Sized,
+      Ser: Serializer,
+{
+    value.serialize_sorted(ser)
+}
+
+/// Deserializes a collection as usual. Provided so the module can be used with `#[serde(with)]`.
+pub fn deserialize<'de, T, D>(de: D) -> Result<T, D::Error>
+where T: SortedSerialize + Deserialize<'de>,
+      D: Deserializer<'de>,
+{
+    T::deserialize(de)
+}
+
+/// A marker that opts the wrapped collection into sorted serialization. Everything else is
+/// forwarded to the collection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Sorted<T>(pub T);
+
+impl<T> Deref for Sorted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Sorted<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: SortedSerialize> Serialize for Sorted<T> {
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize_sorted(ser)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Sorted<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        T::deserialize(de).map(Sorted)
+    }
+}
+
+impl<V, S> HashableHashSet<V, S> {
+    /// Iterates over the elements in ascending order.
+    pub fn iter_sorted(&self) -> std::vec::IntoIter<&V>
+    where V: Ord,
+    {
+        let mut elements: Vec<&V> = self.0.iter().collect();
+        elements.sort_unstable();
+        elements.into_iter()
+    }
+
+    /// Returns clones of the elements in ascending order.
+    pub fn to_sorted_vec(&self) -> Vec<V>
+    where V: Ord + Clone,
+    {
+        self.iter_sorted().cloned().collect()
+    }
+
+    /// Iterates over the elements ordered by a fixed hash of each, for element types without an
+    /// [`Ord`] implementation. The order is the same in every process and on every platform, as
+    /// long as the elements' [`Hash`] implementations do not change.
+    pub fn iter_by_prehash(&self) -> std::vec::IntoIter<&V>
+    where V: Hash,
+    {
+        let mut elements: Vec<&V> = self.0.iter().collect();
+        elements.sort_by_cached_key(stable_prehash);
+        elements.into_iter()
+    }
+}
+
+impl<K, V, S> HashableHashMap<K, V, S> {
+    /// Iterates over the entries in ascending key order.
+    pub fn iter_sorted(&self) -> std::vec::IntoIter<(&K, &V)>
+    where K: Ord,
+    {
+        let mut entries: Vec<(&K, &V)> = self.0.iter().collect();
+        entries.sort_unstable_by_key(|&(k, _)| k);
+        entries.into_iter()
+    }
+
+    /// Returns clones of the entries in ascending key order.
+    pub fn to_sorted_vec(&self) -> Vec<(K, V)>
+    where K: Ord + Clone,
+          V: Clone,
+    {
+        self.iter_sorted().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// Iterates over the entries ordered by a fixed hash of their keys, for key types without an
+    /// [`Ord`] implementation. The order is the same in every process and on every platform, as
+    /// long as the keys' [`Hash`] implementations do not change.
+    pub fn iter_by_prehash(&self) -> std::vec::IntoIter<(&K, &V)>
+    where K: Hash,
+    {
+        let mut entries: Vec<(&K, &V)> = self.0.iter().collect();
+        entries.sort_by_cached_key(|(k, _)| stable_prehash(k));
+        entries.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod sorted_test {
+    use crate::{HashableHashMap, HashableHashSet, Sorted};
+    use std::collections::HashMap;
+
+    #[test]
+    fn marker_sorts_output() {
+        let set: HashableHashSet<_> = vec!["c", "a", "b"].into_iter().collect();
+        assert_eq!(serde_json::to_string(&Sorted(&set)).unwrap(), r#"["a","b","c"]"#);
+
+        let map: HashableHashMap<_, _> = vec![("b", 2), ("c", 3), ("a", 1)].into_iter().collect();
+        assert_eq!(serde_json::to_string(&Sorted(&map)).unwrap(), r#"{"a":1,"b":2,"c":3}"#);
+    }
+
+    #[test]
+    fn with_module_round_trip() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct State {
+            #[serde(with = "crate::sorted")]
+            map: HashMap<u32, bool>,
+            set: Sorted<HashableHashSet<u32>>,
+        }
+
+        let state = State {
+            map: vec![(2, true), (1, false)].into_iter().collect(),
+            set: Sorted(vec![5, 4].into_iter().collect()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"map":{"1":false,"2":true},"set":[4,5]}"#);
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn sorted_iteration() {
+        let set = HashableHashSet::from([3, 1, 2]);
+        assert_eq!(set.iter_sorted().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(set.to_sorted_vec(), [1, 2, 3]);
+
+        let map = HashableHashMap::from([("b", 2), ("a", 1)]);
+        assert_eq!(map.to_sorted_vec(), [("a", 1), ("b", 2)]);
+        assert_eq!(map.iter_sorted().next(), Some((&"a", &1)));
+    }
+
+    #[test]
+    fn prehash_iteration_is_independent_of_insertion_order() {
+        let a: HashableHashSet<HashableHashSet<u32>> =
+            vec![HashableHashSet::from([1]), HashableHashSet::from([2]), HashableHashSet::new()].into_iter().collect();
+        let b: HashableHashSet<HashableHashSet<u32>> =
+            vec![HashableHashSet::new(), HashableHashSet::from([2]), HashableHashSet::from([1])].into_iter().collect();
+        assert!(a.iter_by_prehash().eq(b.iter_by_prehash()));
+        // The order must not depend on the process.
+        let order: Vec<Vec<u32>> = a.iter_by_prehash().map(|set| set.iter().copied().collect()).collect();
+        assert_eq!(order, [vec![1], vec![2], vec![]]);
+    }
+}
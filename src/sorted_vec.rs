@@ -0,0 +1,400 @@
+//! Hashable collections backed by sorted vectors.
+//!
+//! For small collections these use less memory than hash tables and iterate in ascending order.
+//! Because their contents are already sorted, they hash their entries in sequence rather than
+//! pre-hashing them, so their hashes differ from those of the hash-table-backed wrappers.
+
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+
+/// A set stored as a sorted vector without duplicates. Lookups and removals are logarithmic, and
+/// insertions are linear in the size of the set.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableVecSet;
+///
+/// let mut set: HashableVecSet<u32> = vec![3, 1, 3].into_iter().collect();
+/// assert!(set.insert(2));
+/// assert_eq!(set.as_slice(), [1, 2, 3]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HashableVecSet<V>(Vec<V>);
+
+impl<V> HashableVecSet<V> {
+    #[inline]
+    pub fn new() -> HashableVecSet<V> {
+        HashableVecSet(Vec::new())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableVecSet<V> {
+        HashableVecSet(Vec::with_capacity(capacity))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the elements in ascending order.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, V> {
+        self.0.iter()
+    }
+
+    /// Returns the elements in ascending order.
+    #[inline]
+    pub fn as_slice(&self) -> &[V] {
+        &self.0
+    }
+
+    /// Consumes the set, returning its elements in ascending order.
+    #[inline]
+    pub fn into_vec(self) -> Vec<V> {
+        self.0
+    }
+}
+
+impl<V: Ord> HashableVecSet<V> {
+    /// Adds a value, returning whether it was newly added.
+    pub fn insert(&mut self, value: V) -> bool {
+        match self.0.binary_search(&value) {
+            Ok(_) => false,
+            Err(i) => {
+                self.0.insert(i, value);
+                true
+            }
+        }
+    }
+
+    /// Removes a value, returning whether it was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        match self.0.binary_search_by(|v| v.borrow().cmp(value)) {
+            Ok(i) => {
+                self.0.remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where V: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.0.binary_search_by(|v| v.borrow().cmp(value)).is_ok()
+    }
+}
+
+impl<V: Debug> Debug for HashableVecSet<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.0.iter()).finish()
+    }
+}
+
+impl<V> Default for HashableVecSet<V> {
+    #[inline]
+    fn default() -> Self {
+        HashableVecSet::new()
+    }
+}
+
+impl<'de, V: Ord + Deserialize<'de>> Deserialize<'de> for HashableVecSet<V> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Vec::deserialize(de).map(HashableVecSet::from)
+    }
+}
+
+impl<V: Ord> Extend<V> for HashableVecSet<V> {
+    fn extend<T: IntoIterator<Item=V>>(&mut self, iter: T) {
+        self.0.extend(iter);
+        self.0.sort();
+        self.0.dedup();
+    }
+}
+
+impl<V: Ord> From<Vec<V>> for HashableVecSet<V> {
+    /// Sorts and deduplicates `values`.
+    fn from(mut values: Vec<V>) -> Self {
+        values.sort();
+        values.dedup();
+        HashableVecSet(values)
+    }
+}
+
+impl<V: Ord, S> From<HashableHashSet<V, S>> for HashableVecSet<V> {
+    fn from(set: HashableHashSet<V, S>) -> Self {
+        let mut values: Vec<V> = set.into_iter().collect();
+        values.sort_unstable();
+        HashableVecSet(values)
+    }
+}
+
+impl<V: Ord> FromIterator<V> for HashableVecSet<V> {
+    fn from_iter<T: IntoIterator<Item=V>>(iter: T) -> Self {
+        HashableVecSet::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a, V> IntoIterator for &'a HashableVecSet<V> {
+    type Item = &'a V;
+    type IntoIter = std::slice::Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> std::slice::Iter<'a, V> {
+        self.0.iter()
+    }
+}
+
+impl<V> IntoIterator for HashableVecSet<V> {
+    type Item = V;
+    type IntoIter = std::vec::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> std::vec::IntoIter<V> {
+        self.0.into_iter()
+    }
+}
+
+impl<V: Serialize> Serialize for HashableVecSet<V> {
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(ser)
+    }
+}
+
+/// A map stored as a vector of entries sorted by key, with unique keys. Lookups and removals are
+/// logarithmic, and insertions of new keys are linear in the size of the map.
+///
+/// # Example
+///
+/// ```rust
+/// use hashable::HashableVecMap;
+///
+/// let mut map: HashableVecMap<&str, u32> = vec![("b", 2), ("a", 1)].into_iter().collect();
+/// assert_eq!(map.insert("a", 3), Some(1));
+/// assert_eq!(map.iter().collect::<Vec<_>>(), vec![(&"a", &3), (&"b", &2)]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HashableVecMap<K, V>(Vec<(K, V)>);
+
+impl<K, V> HashableVecMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableVecMap<K, V> {
+        HashableVecMap(Vec::new())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableVecMap<K, V> {
+        HashableVecMap(Vec::with_capacity(capacity))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the entries in ascending key order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item=(&K, &V)> + ExactSizeIterator {
+        self.0.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterates over the keys in ascending order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item=&K> + ExactSizeIterator {
+        self.0.iter().map(|(k, _)| k)
+    }
+
+    /// Iterates over the values in ascending key order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item=&V> + ExactSizeIterator {
+        self.0.iter().map(|(_, v)| v)
+    }
+
+    /// Returns the entries in ascending key order.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.0
+    }
+
+    /// Consumes the map, returning its entries in ascending key order.
+    #[inline]
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.0
+    }
+}
+
+impl<K: Ord, V> HashableVecMap<K, V> {
+    fn position<Q>(&self, key: &Q) -> Result<usize, usize>
+    where K: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.0.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    /// Inserts a key-value pair, returning the previous value for the key.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.position(&key) {
+            Ok(i) => Some(std::mem::replace(&mut self.0[i].1, value)),
+            Err(i) => {
+                self.0.insert(i, (key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes a key, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where K: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.position(key).ok().map(|i| self.0.remove(i).1)
+    }
+
+    /// Returns the value for `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where K: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.position(key).ok().map(|i| &self.0[i].1)
+    }
+
+    /// Returns a mutable reference to the value for `key`.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where K: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.position(key).ok().map(move |i| &mut self.0[i].1)
+    }
+
+    /// Returns `true` if the map contains `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where K: Borrow<Q>,
+          Q: Ord + ?Sized,
+    {
+        self.position(key).is_ok()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for HashableVecMap<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Default for HashableVecMap<K, V> {
+    #[inline]
+    fn default() -> Self {
+        HashableVecMap::new()
+    }
+}
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for HashableVecMap<K, V> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        BTreeMap::deserialize(de).map(|map| HashableVecMap(map.into_iter().collect()))
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for HashableVecMap<K, V> {
+    fn extend<T: IntoIterator<Item=(K, V)>>(&mut self, iter: T) {
+        let mut entries = std::mem::take(&mut self.0);
+        entries.extend(iter);
+        *self = HashableVecMap::from(entries);
+    }
+}
+
+impl<K: Ord, V> From<Vec<(K, V)>> for HashableVecMap<K, V> {
+    /// Sorts `entries` by key. When a key repeats, the last entry for it wins.
+    fn from(mut entries: Vec<(K, V)>) -> Self {
+        // A stable sort of the reversed entries puts the last entry for each key first, which is
+        // the one `dedup_by` keeps.
+        entries.reverse();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.dedup_by(|(a, _), (b, _)| a == b);
+        HashableVecMap(entries)
+    }
+}
+
+impl<K: Ord, V, S> From<HashableHashMap<K, V, S>> for HashableVecMap<K, V> {
+    fn from(map: HashableHashMap<K, V, S>) -> Self {
+        let mut entries: Vec<(K, V)> = map.into_iter().collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        HashableVecMap(entries)
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for HashableVecMap<K, V> {
+    fn from_iter<T: IntoIterator<Item=(K, V)>>(iter: T) -> Self {
+        HashableVecMap::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<K, V> IntoIterator for HashableVecMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    #[inline]
+    fn into_iter(self) -> std::vec::IntoIter<(K, V)> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for HashableVecMap<K, V> {
+    fn serialize<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        ser.collect_map(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod sorted_vec_test {
+    use crate::{hash, HashableHashMap, HashableHashSet, HashableVecMap, HashableVecSet};
+
+    #[test]
+    fn set_operations() {
+        let mut set: HashableVecSet<u32> = vec![3, 1, 2, 1].into_iter().collect();
+        assert_eq!(set.as_slice(), [1, 2, 3]);
+        assert!(!set.insert(2));
+        assert!(set.remove(&2));
+        assert!(!set.contains(&2));
+        assert_eq!(set, HashableVecSet::from(HashableHashSet::from([3, 1])));
+        assert_eq!(hash(&set), hash(&vec![1, 3].into_iter().collect::<HashableVecSet<u32>>()));
+    }
+
+    #[test]
+    fn map_last_entry_wins() {
+        let map: HashableVecMap<&str, u32> = vec![("b", 1), ("a", 2), ("b", 3)].into_iter().collect();
+        assert_eq!(map.as_slice(), [("a", 2), ("b", 3)]);
+        assert_eq!(map, HashableVecMap::from(HashableHashMap::from([("b", 3), ("a", 2)])));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let set: HashableVecSet<u32> = vec![2, 1].into_iter().collect();
+        assert_eq!(serde_json::to_string(&set).unwrap(), "[1,2]");
+        assert_eq!(serde_json::from_str::<HashableVecSet<u32>>("[2,1,2]").unwrap(), set);
+
+        let mut map: HashableVecMap<String, u32> = HashableVecMap::new();
+        map.insert("b".to_string(), 2);
+        map.insert("a".to_string(), 1);
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"a":1,"b":2}"#);
+        assert_eq!(serde_json::from_str::<HashableVecMap<String, u32>>(&json).unwrap(), map);
+    }
+}
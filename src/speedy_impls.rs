@@ -0,0 +1,80 @@
+//! [`speedy`] implementations that share the encoding of the inner std collections.
+
+use crate::{HashableHashMap, HashableHashSet};
+use speedy::{Context, Readable, Reader, Writable, Writer};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+impl<C: Context, V: Writable<C>, S> Writable<C> for HashableHashSet<V, S> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.0.write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.0.bytes_needed()
+    }
+}
+
+impl<'a, C, V, S> Readable<'a, C> for HashableHashSet<V, S>
+where C: Context,
+      V: Readable<'a, C> + Eq + Hash,
+      S: BuildHasher + Default,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        HashSet::read_from(reader).map(HashableHashSet)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        HashSet::<V, S>::minimum_bytes_needed()
+    }
+}
+
+impl<C: Context, K: Writable<C>, V: Writable<C>, S> Writable<C> for HashableHashMap<K, V, S> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.0.write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.0.bytes_needed()
+    }
+}
+
+impl<'a, C, K, V, S> Readable<'a, C> for HashableHashMap<K, V, S>
+where C: Context,
+      K: Readable<'a, C> + Eq + Hash,
+      V: Readable<'a, C>,
+      S: BuildHasher + Default,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        HashMap::read_from(reader).map(HashableHashMap)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        HashMap::<K, V, S>::minimum_bytes_needed()
+    }
+}
+
+#[cfg(test)]
+mod speedy_impls_test {
+    use crate::{HashableHashMap, HashableHashSet};
+    use speedy::{Readable, Writable};
+
+    #[test]
+    fn round_trip() {
+        let mut map: HashableHashMap<String, HashableHashSet<u32>> = HashableHashMap::new();
+        map.insert("key".to_string(), vec![1, 2, 3].into_iter().collect());
+
+        let bytes = map.write_to_vec().unwrap();
+        assert_eq!(bytes.len(), Writable::<speedy::LittleEndian>::bytes_needed(&map).unwrap());
+        let decoded = HashableHashMap::<String, HashableHashSet<u32>>::read_from_buffer(&bytes).unwrap();
+        assert_eq!(decoded, map);
+    }
+}
@@ -0,0 +1,144 @@
+//! A fixed hash function for values that leave the process, such as checksums, files, and digests
+//! exchanged between replicas.
+//!
+//! The pre-hashes that the wrappers feed into [`Hash`] come from [`ahash::AHasher::default`],
+//! whose keys are chosen at random once per process, so they must never be stored or sent
+//! elsewhere. [`stable_prehash`] instead uses [`StableHasher`], and while it runs, nested hashable
+//! collections pre-hash their entries with [`StableHasher`] too, so the result of hashing a value
+//! depends only on the value.
+
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+
+thread_local!(static ACTIVE: Cell<bool> = const { Cell::new(false) });
+
+/// Returns whether pre-hashes on the current thread must use [`StableHasher`].
+pub(crate) fn active() -> bool {
+    ACTIVE.with(Cell::get)
+}
+
+/// Hashes `value` with [`StableHasher`], including the entries of any hashable collections nested
+/// within it.
+pub(crate) fn stable_prehash<T: Hash + ?Sized>(value: &T) -> u64 {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ACTIVE.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(ACTIVE.with(|cell| cell.replace(true)));
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 64-bit FNV-1a over the bytes written, with integers written in little-endian order and `usize`
+/// and `isize` widened to 64 bits, followed by the SplitMix64 finalizer. The output does not
+/// depend on the process, the platform, or the version of any dependency.
+pub(crate) struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    #[inline]
+    fn default() -> Self {
+        StableHasher { state: 0xcbf2_9ce4_8422_2325 }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        crate::sketch::mix(self.state, 0)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state = (self.state ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, n: u8) {
+        self.write(&[n]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, n: i8) {
+        self.write_u8(n as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, n: i16) {
+        self.write_u16(n as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, n: i32) {
+        self.write_u32(n as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, n: i64) {
+        self.write_u64(n as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, n: i128) {
+        self.write_u128(n as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, n: isize) {
+        self.write_i64(n as i64);
+    }
+}
+
+#[cfg(test)]
+mod stable_test {
+    use super::stable_prehash;
+    use crate::{prehash, HashableHashMap, HashableHashSet};
+
+    #[test]
+    fn fixed_values() {
+        // These must never change: they are written to files and exchanged between processes.
+        assert_eq!(stable_prehash(&1u32), 16040831228450459584);
+        assert_eq!(stable_prehash("abc"), 913532767295458681);
+        assert_eq!(stable_prehash(&HashableHashSet::from([1u32, 2, 3])), 2605832796694582571);
+    }
+
+    #[test]
+    fn nested_collections_are_stable() {
+        let a = HashableHashMap::from([(1u8, HashableHashSet::from(["x", "y"]))]);
+        let b = HashableHashMap::from([(1u8, HashableHashSet::from(["y", "x"]))]);
+        assert_eq!(stable_prehash(&a), stable_prehash(&b));
+        assert_ne!(stable_prehash(&a), prehash(&a));
+        assert!(!super::active());
+    }
+}
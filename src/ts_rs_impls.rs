@@ -0,0 +1,103 @@
+//! [`TS`] implementations that export the same TypeScript types as the inner std collections.
+
+use crate::{HashableHashMap, HashableHashSet};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use ts_rs::{TypeVisitor, TS};
+
+impl<V: TS, S> TS for HashableHashSet<V, S> {
+    type WithoutGenerics = <HashSet<V> as TS>::WithoutGenerics;
+    type OptionInnerType = <HashSet<V> as TS>::OptionInnerType;
+
+    fn ident() -> String {
+        HashSet::<V>::ident()
+    }
+
+    fn name() -> String {
+        HashSet::<V>::name()
+    }
+
+    fn inline() -> String {
+        HashSet::<V>::inline()
+    }
+
+    fn inline_flattened() -> String {
+        HashSet::<V>::inline_flattened()
+    }
+
+    fn visit_dependencies(v: &mut impl TypeVisitor) where Self: 'static {
+        HashSet::<V>::visit_dependencies(v)
+    }
+
+    fn visit_generics(v: &mut impl TypeVisitor) where Self: 'static {
+        HashSet::<V>::visit_generics(v)
+    }
+
+    fn decl() -> String {
+        HashSet::<V>::decl()
+    }
+
+    fn decl_concrete() -> String {
+        HashSet::<V>::decl_concrete()
+    }
+
+    fn output_path() -> Option<PathBuf> {
+        HashSet::<V>::output_path()
+    }
+}
+
+impl<K: TS, V: TS, S> TS for HashableHashMap<K, V, S> {
+    type WithoutGenerics = <HashMap<K, V> as TS>::WithoutGenerics;
+    type OptionInnerType = <HashMap<K, V> as TS>::OptionInnerType;
+
+    fn ident() -> String {
+        HashMap::<K, V>::ident()
+    }
+
+    fn name() -> String {
+        HashMap::<K, V>::name()
+    }
+
+    fn inline() -> String {
+        HashMap::<K, V>::inline()
+    }
+
+    fn inline_flattened() -> String {
+        HashMap::<K, V>::inline_flattened()
+    }
+
+    fn visit_dependencies(v: &mut impl TypeVisitor) where Self: 'static {
+        HashMap::<K, V>::visit_dependencies(v)
+    }
+
+    fn visit_generics(v: &mut impl TypeVisitor) where Self: 'static {
+        HashMap::<K, V>::visit_generics(v)
+    }
+
+    fn decl() -> String {
+        HashMap::<K, V>::decl()
+    }
+
+    fn decl_concrete() -> String {
+        HashMap::<K, V>::decl_concrete()
+    }
+
+    fn output_path() -> Option<PathBuf> {
+        HashMap::<K, V>::output_path()
+    }
+}
+
+#[cfg(test)]
+mod ts_rs_impls_test {
+    use crate::{HashableHashMap, HashableHashSet};
+    use std::collections::{HashMap, HashSet};
+    use ts_rs::TS;
+
+    #[test]
+    fn types_match_std() {
+        assert_eq!(HashableHashSet::<u32>::name(), HashSet::<u32>::name());
+        assert_eq!(
+            HashableHashMap::<String, HashableHashSet<u32>>::inline(),
+            HashMap::<String, HashSet<u32>>::inline());
+    }
+}
@@ -0,0 +1,118 @@
+//! A serde `with` module for plain [`HashSet`] and [`HashMap`] fields (and the hashable wrappers).
+//!
+//! Serialization emits entries ordered by a fixed hash of each element (or key), so equal
+//! collections produce the same output regardless of insertion order, [`BuildHasher`] state, or
+//! the process that wrote them. Deserialization is unchanged.
+//!
+//! The fixed hash does not depend on the process or platform, but it does depend on the entries'
+//! [`Hash`] implementations, so prefer a sort based on [`Ord`] if those may change.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct State {
+//!     #[serde(with = "hashable::unordered")]
+//!     counts: HashMap<String, u32>,
+//! }
+//! ```
+//!
+//! [`HashableHashSet`]: crate::HashableHashSet
+//! [`HashableHashMap`]: crate::HashableHashMap
+//! [`BuildHasher`]: std::hash::BuildHasher
+
+use crate::stable::stable_prehash;
+use crate::{HashableHashMap, HashableHashSet};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A collection that can be serialized via [`serialize`].
+pub trait Unordered {
+    /// Serializes the collection with its entries in a fixed order.
+    fn serialize_unordered<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error>;
+}
+
+impl<V: Hash + Serialize, S> Unordered for HashSet<V, S> {
+    fn serialize_unordered<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut entries: Vec<_> = self.iter().map(|v| (stable_prehash(v), v)).collect();
+        entries.sort_unstable_by_key(|(hash, _)| *hash);
+        ser.collect_seq(entries.into_iter().map(|(_, v)| v))
+    }
+}
+
+impl<K: Hash + Serialize, V: Serialize, S> Unordered for HashMap<K, V, S> {
+    fn serialize_unordered<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        // Keys are unique, so they alone determine the order.
+        let mut entries: Vec<_> = self.iter().map(|(k, v)| (stable_prehash(k), k, v)).collect();
+        entries.sort_unstable_by_key(|(hash, _, _)| *hash);
+        ser.collect_map(entries.into_iter().map(|(_, k, v)| (k, v)))
+    }
+}
+
+impl<V: Hash + Serialize, S> Unordered for HashableHashSet<V, S> {
+    fn serialize_unordered<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (**self).serialize_unordered(ser)
+    }
+}
+
+impl<K: Hash + Serialize, V: Serialize, S> Unordered for HashableHashMap<K, V, S> {
+    fn serialize_unordered<Ser: Serializer>(&self, ser: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (**self).serialize_unordered(ser)
+    }
+}
+
+/// Serializes a collection with its entries in a fixed order.
+pub fn serialize<T, Ser>(value: &T, ser: Ser) -> Result<Ser::Ok, Ser::Error>
+where T: Unordered + ?Sized,
+      Ser: Serializer,
+{
+    value.serialize_unordered(ser)
+}
+
+/// Deserializes a collection as usual. Provided so the module can be used with `#[serde(with)]`.
+pub fn deserialize<'de, T, D>(de: D) -> Result<T, D::Error>
+where T: Unordered + Deserialize<'de>,
+      D: Deserializer<'de>,
+{
+    T::deserialize(de)
+}
+
+#[cfg(test)]
+mod unordered_test {
+    use std::collections::{HashMap, HashSet};
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct State {
+        #[serde(with = "crate::unordered")]
+        set: HashSet<String>,
+        #[serde(with = "crate::unordered")]
+        map: HashMap<String, u32>,
+    }
+
+    fn state(names: &[&str]) -> State {
+        State {
+            set: names.iter().map(|s| s.to_string()).collect(),
+            map: names.iter().map(|s| (s.to_string(), s.len() as u32)).collect(),
+        }
+    }
+
+    #[test]
+    fn output_is_independent_of_insertion_order_and_hasher() {
+        let json1 = serde_json::to_string(&state(&["one", "two", "three", "four"])).unwrap();
+        let json2 = serde_json::to_string(&state(&["three", "one", "four", "two"])).unwrap();
+        assert_eq!(json1, json2);
+        // The order must not depend on the process.
+        assert_eq!(json1, r#"{"set":["one","four","three","two"],"map":{"one":3,"four":4,"three":5,"two":3}}"#);
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = serde_json::to_string(&state(&["one", "two"])).unwrap();
+        let round_tripped: State = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.set, state(&["one", "two"]).set);
+        assert_eq!(round_tripped.map, state(&["one", "two"]).map);
+    }
+}
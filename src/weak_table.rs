@@ -0,0 +1,312 @@
+//! Hashable wrappers around [`weak_table`]'s collections with weakly held keys.
+//!
+//! Entries whose referents have been dropped are skipped when hashing and comparing, so a
+//! collection hashes like a [`crate::HashableHashSet`] (or [`crate::HashableHashMap`]) of its live
+//! entries. Dead entries still occupy memory until `remove_expired` is called.
+//!
+//! # Example
+//!
+//! ```rust
+//! use hashable::weak_table::HashableWeakHashSet;
+//! use std::rc::{Rc, Weak};
+//!
+//! let a: Rc<str> = Rc::from("a");
+//! let mut cache: HashableWeakHashSet<Weak<str>> = HashableWeakHashSet::new();
+//! cache.insert(a.clone());
+//! cache.insert(Rc::from("dropped"));
+//! assert_eq!(cache.iter().count(), 1);
+//! ```
+
+use crate::{hash_prehashes, prehash};
+use ::weak_table::traits::WeakKey;
+use ::weak_table::{WeakHashSet, WeakKeyHashMap};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// Hashes a strong reference by its key, like the collections do.
+struct ByKey<'a, T: WeakKey>(&'a T::Strong);
+
+impl<T: WeakKey> Hash for ByKey<'_, T> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        T::hash(self.0, hasher)
+    }
+}
+
+/// A [`WeakHashSet`] wrapper that implements [`Hash`] like [`crate::HashableHashSet`], over the
+/// live elements only.
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableWeakHashSet<T, S = ahash::RandomState>(WeakHashSet<T, S>);
+
+impl<T: WeakKey> HashableWeakHashSet<T> {
+    #[inline]
+    pub fn new() -> HashableWeakHashSet<T> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableWeakHashSet<T> {
+        HashableWeakHashSet::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<T: WeakKey, S: BuildHasher> HashableWeakHashSet<T, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableWeakHashSet(WeakHashSet::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableWeakHashSet(WeakHashSet::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<T, S> HashableWeakHashSet<T, S> {
+    /// Consumes the wrapper, returning the inner [`WeakHashSet`].
+    #[inline]
+    pub fn into_inner(self) -> WeakHashSet<T, S> {
+        self.0
+    }
+}
+
+impl<T: WeakKey, S> Debug for HashableWeakHashSet<T, S>
+where T::Strong: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<T: WeakKey, S: BuildHasher + Default> Default for HashableWeakHashSet<T, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableWeakHashSet(WeakHashSet::default())
+    }
+}
+
+impl<T, S> Deref for HashableWeakHashSet<T, S> {
+    type Target = WeakHashSet<T, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, S> DerefMut for HashableWeakHashSet<T, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: WeakKey, S: BuildHasher> Eq for HashableWeakHashSet<T, S> {}
+
+impl<T: WeakKey, S: BuildHasher> Extend<T::Strong> for HashableWeakHashSet<T, S> {
+    #[inline]
+    fn extend<I: IntoIterator<Item=T::Strong>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<T, S> From<WeakHashSet<T, S>> for HashableWeakHashSet<T, S> {
+    #[inline]
+    fn from(set: WeakHashSet<T, S>) -> Self {
+        HashableWeakHashSet(set)
+    }
+}
+
+impl<T: WeakKey, S: BuildHasher + Default> FromIterator<T::Strong> for HashableWeakHashSet<T, S> {
+    fn from_iter<I: IntoIterator<Item=T::Strong>>(iter: I) -> Self {
+        HashableWeakHashSet(WeakHashSet::from_iter(iter))
+    }
+}
+
+impl<T: WeakKey, S> Hash for HashableWeakHashSet<T, S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hash_prehashes(self.0.iter().map(|v| prehash(&ByKey::<T>(&v))), hasher);
+    }
+}
+
+impl<'a, T: WeakKey, S> IntoIterator for &'a HashableWeakHashSet<T, S> {
+    type Item = T::Strong;
+    type IntoIter = ::weak_table::weak_hash_set::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> ::weak_table::weak_hash_set::Iter<'a, T> {
+        self.0.iter()
+    }
+}
+
+impl<T: WeakKey, S> IntoIterator for HashableWeakHashSet<T, S> {
+    type Item = T::Strong;
+    type IntoIter = ::weak_table::weak_hash_set::IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> ::weak_table::weak_hash_set::IntoIter<T> {
+        self.0.into_iter()
+    }
+}
+
+impl<T: WeakKey, S: BuildHasher> PartialEq for HashableWeakHashSet<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+/// A [`WeakKeyHashMap`] wrapper that implements [`Hash`] like [`crate::HashableHashMap`], over the
+/// entries with live keys only.
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct HashableWeakKeyHashMap<K, V, S = ahash::RandomState>(WeakKeyHashMap<K, V, S>);
+
+impl<K: WeakKey, V> HashableWeakKeyHashMap<K, V> {
+    #[inline]
+    pub fn new() -> HashableWeakKeyHashMap<K, V> {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> HashableWeakKeyHashMap<K, V> {
+        HashableWeakKeyHashMap::with_capacity_and_hasher(capacity, Default::default())
+    }
+}
+
+impl<K: WeakKey, V, S: BuildHasher> HashableWeakKeyHashMap<K, V, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        HashableWeakKeyHashMap(WeakKeyHashMap::with_hasher(hasher))
+    }
+
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashableWeakKeyHashMap(WeakKeyHashMap::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<K, V, S> HashableWeakKeyHashMap<K, V, S> {
+    /// Consumes the wrapper, returning the inner [`WeakKeyHashMap`].
+    #[inline]
+    pub fn into_inner(self) -> WeakKeyHashMap<K, V, S> {
+        self.0
+    }
+}
+
+impl<K: WeakKey, V: Debug, S> Debug for HashableWeakKeyHashMap<K, V, S>
+where K::Strong: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt(f) // transparent
+    }
+}
+
+impl<K: WeakKey, V, S: BuildHasher + Default> Default for HashableWeakKeyHashMap<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        HashableWeakKeyHashMap(WeakKeyHashMap::default())
+    }
+}
+
+impl<K, V, S> Deref for HashableWeakKeyHashMap<K, V, S> {
+    type Target = WeakKeyHashMap<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K, V, S> DerefMut for HashableWeakKeyHashMap<K, V, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K: WeakKey, V: Eq, S: BuildHasher> Eq for HashableWeakKeyHashMap<K, V, S> {}
+
+impl<K: WeakKey, V, S: BuildHasher> Extend<(K::Strong, V)> for HashableWeakKeyHashMap<K, V, S> {
+    #[inline]
+    fn extend<I: IntoIterator<Item=(K::Strong, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> From<WeakKeyHashMap<K, V, S>> for HashableWeakKeyHashMap<K, V, S> {
+    #[inline]
+    fn from(map: WeakKeyHashMap<K, V, S>) -> Self {
+        HashableWeakKeyHashMap(map)
+    }
+}
+
+impl<K: WeakKey, V, S: BuildHasher + Default> FromIterator<(K::Strong, V)> for HashableWeakKeyHashMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item=(K::Strong, V)>>(iter: I) -> Self {
+        HashableWeakKeyHashMap(WeakKeyHashMap::from_iter(iter))
+    }
+}
+
+impl<K: WeakKey, V: Hash, S> Hash for HashableWeakKeyHashMap<K, V, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_prehashes(self.0.iter().map(|(k, v)| prehash(&(ByKey::<K>(&k), v))), state);
+    }
+}
+
+impl<'a, K: WeakKey, V, S> IntoIterator for &'a HashableWeakKeyHashMap<K, V, S> {
+    type Item = (K::Strong, &'a V);
+    type IntoIter = ::weak_table::weak_key_hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::weak_table::weak_key_hash_map::Iter<'a, K, V> {
+        self.0.iter()
+    }
+}
+
+impl<K: WeakKey, V, S> IntoIterator for HashableWeakKeyHashMap<K, V, S> {
+    type Item = (K::Strong, V);
+    type IntoIter = ::weak_table::weak_key_hash_map::IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> ::weak_table::weak_key_hash_map::IntoIter<K, V> {
+        self.0.into_iter()
+    }
+}
+
+impl<K: WeakKey, V: PartialEq, S: BuildHasher> PartialEq for HashableWeakKeyHashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod weak_table_test {
+    use super::{HashableWeakHashSet, HashableWeakKeyHashMap};
+    use crate::hash;
+    use std::rc::{Rc, Weak};
+
+    #[test]
+    fn hash_matches_std_wrapper_for_live_entries() {
+        let a: Rc<str> = Rc::from("a");
+        let b: Rc<str> = Rc::from("b");
+        let mut set: HashableWeakHashSet<Weak<str>> = HashableWeakHashSet::new();
+        set.extend(vec![a.clone(), b.clone(), Rc::from("dropped")]);
+        let std_set: crate::HashableHashSet<&str> = vec!["b", "a"].into_iter().collect();
+        assert_eq!(hash(&set), hash(&std_set));
+
+        let mut map: HashableWeakKeyHashMap<Weak<str>, u32> = HashableWeakKeyHashMap::new();
+        map.extend(vec![(a.clone(), 1), (Rc::from("dropped"), 2)]);
+        let std_map: crate::HashableHashMap<&str, u32> = vec![("a", 1)].into_iter().collect();
+        assert_eq!(hash(&map), hash(&std_map));
+    }
+
+    #[test]
+    fn dropping_a_referent_changes_the_hash() {
+        let a: Rc<str> = Rc::from("a");
+        let b: Rc<str> = Rc::from("b");
+        let set: HashableWeakHashSet<Weak<str>> = vec![a.clone(), b.clone()].into_iter().collect();
+        let before = hash(&set);
+        drop(b);
+        let only_a: HashableWeakHashSet<Weak<str>> = vec![a].into_iter().collect();
+        assert_ne!(hash(&set), before);
+        assert_eq!(hash(&set), hash(&only_a));
+        assert_eq!(set, only_a);
+    }
+}